@@ -4,6 +4,7 @@ use crate::rocks::util::engine_metrics::*;
 use crate::rocks::{
     CompactionJobInfo, FlushJobInfo, IngestionInfo, WriteStallCondition, WriteStallInfo,
 };
+use tikv_util::io_tags::{add_io_bytes, IOOp, IOType};
 
 pub struct EventListener {
     db_name: String,
@@ -31,6 +32,11 @@ impl engine_rocksdb::EventListener for EventListener {
         STORE_ENGINE_EVENT_COUNTER_VEC
             .with_label_values(&[&self.db_name, info.cf_name(), "flush"])
             .inc();
+        add_io_bytes(
+            IOType::Flush,
+            IOOp::Write,
+            info.table_properties().data_size(),
+        );
         STORE_ENGINE_STALL_CONDITIONS_CHANGED_VEC
             .with_label_values(&[&self.db_name, info.cf_name(), "triggered_writes_slowdown"])
             .set(info.triggered_writes_slowdown() as i64);
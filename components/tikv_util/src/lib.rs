@@ -52,6 +52,7 @@ pub mod config;
 pub mod file;
 pub mod future;
 pub mod future_pool;
+pub mod io_tags;
 #[macro_use]
 pub mod macros;
 pub mod keybuilder;
@@ -561,6 +561,8 @@ trait DebugExecutor {
     fn dump_metrics(&self, tags: Vec<&str>);
 
     fn dump_region_properties(&self, region_id: u64);
+
+    fn dump_sst_properties(&self, cf: &str, start: Vec<u8>, end: Vec<u8>, verify: bool);
 }
 
 impl DebugExecutor for DebugClient {
@@ -747,6 +749,10 @@ impl DebugExecutor for DebugClient {
             v1!("{}: {}", prop.get_name(), prop.get_value());
         }
     }
+
+    fn dump_sst_properties(&self, _: &str, _: Vec<u8>, _: Vec<u8>, _: bool) {
+        self.check_local_mode();
+    }
 }
 
 impl DebugExecutor for Debugger {
@@ -947,6 +953,25 @@ impl DebugExecutor for Debugger {
             v1!("{}: {}", name, value);
         }
     }
+
+    fn dump_sst_properties(&self, cf: &str, start: Vec<u8>, end: Vec<u8>, verify: bool) {
+        let ssts = self
+            .get_sst_properties(cf, &start, &end)
+            .unwrap_or_else(|e| perror_and_exit("Debugger::get_sst_properties", e));
+        v1!("{} SST files overlap the range", ssts.len());
+        for (file, props) in ssts {
+            v1!("sst file: {}", file);
+            for (name, value) in props {
+                v1!("    {}: {}", name, value);
+            }
+        }
+        if verify {
+            let scanned = self
+                .verify_range_checksum(cf, &start, &end)
+                .unwrap_or_else(|e| perror_and_exit("Debugger::verify_range_checksum", e));
+            v1!("verified {} keys, no corruption detected", scanned);
+        }
+    }
 }
 
 fn main() {
@@ -1628,6 +1653,38 @@ fn main() {
                         .help("The target region id"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("sst-properties")
+                .about("Show properties of the SST files overlapping a key range in the kv db")
+                .arg(
+                    Arg::with_name("cf")
+                        .short("c")
+                        .takes_value(true)
+                        .default_value(CF_DEFAULT)
+                        .possible_values(&["default", "lock", "write"])
+                        .help("The column family name"),
+                )
+                .arg(
+                    Arg::with_name("from")
+                        .short("f")
+                        .long("from")
+                        .takes_value(true)
+                        .help(raw_key_hint),
+                )
+                .arg(
+                    Arg::with_name("to")
+                        .short("t")
+                        .long("to")
+                        .takes_value(true)
+                        .help(raw_key_hint),
+                )
+                .arg(
+                    Arg::with_name("verify")
+                        .long("verify")
+                        .takes_value(false)
+                        .help("Scan the range afterwards to verify block checksums"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("split-region")
                 .about("Split the region")
@@ -1966,6 +2023,12 @@ fn main() {
     } else if let Some(matches) = matches.subcommand_matches("region-properties") {
         let region_id = value_t_or_exit!(matches.value_of("region"), u64);
         debug_executor.dump_region_properties(region_id)
+    } else if let Some(matches) = matches.subcommand_matches("sst-properties") {
+        let cf = matches.value_of("cf").unwrap();
+        let start = matches.value_of("from").map_or_else(Vec::new, |k| unescape(k));
+        let end = matches.value_of("to").map_or_else(Vec::new, |k| unescape(k));
+        let verify = matches.is_present("verify");
+        debug_executor.dump_sst_properties(cf, start, end, verify)
     } else if let Some(matches) = matches.subcommand_matches("fail") {
         if host.is_none() {
             ve1!("command fail requires host");
@@ -12,9 +12,12 @@ use crate::raftstore::coprocessor::{CoprocessorHost, RegionInfoAccessor};
 use crate::raftstore::store::fsm::store::{StoreMeta, PENDING_VOTES_CAP};
 use crate::raftstore::store::{fsm, LocalReader};
 use crate::raftstore::store::{new_compaction_listener, SnapManagerBuilder};
+use crate::server::block_cache_warmup;
 use crate::server::resolve;
+use crate::server::stats_monitor::{StatsMonitor, DEFAULT_STATS_MONITOR_INTERVAL};
 use crate::server::status_server::StatusServer;
 use crate::server::transport::ServerRaftStoreRouter;
+use crate::server::DATA_PATH_SIZE_GAUGE_VEC;
 use crate::server::DEFAULT_CLUSTER_ID;
 use crate::server::{create_raft_storage, Node, Server};
 use crate::storage::lock_manager::{
@@ -29,7 +32,7 @@ use fs2::FileExt;
 use std::fs::File;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
-use std::thread::JoinHandle;
+use std::thread::{self, JoinHandle};
 use std::time::Duration;
 use tikv_util::check_environment_variables;
 use tikv_util::security::SecurityManager;
@@ -38,6 +41,8 @@ use tikv_util::worker::FutureWorker;
 
 const RESERVED_OPEN_FDS: u64 = 1000;
 
+const DATA_PATH_METRICS_INTERVAL: Duration = Duration::from_secs(60);
+
 pub fn run_tikv(mut config: TiKvConfig) {
     if let Err(e) = check_and_persist_critical_config(&config) {
         fatal!("critical config check failed: {}", e);
@@ -158,6 +163,12 @@ fn run_raft_server(pd_client: RpcClient, cfg: &TiKvConfig, security_mgr: Arc<Sec
     if let Some(ec) = encrypted_env {
         kv_db_opts.set_env(ec);
     }
+    // Place cold (bottommost) SST files on the secondary data dir when one
+    // is configured, keeping the hot levels on the primary device.
+    let kv_db_paths = cfg.rocksdb.build_db_paths(db_path.to_str().unwrap());
+    if !kv_db_paths.is_empty() {
+        kv_db_opts.set_db_paths(&kv_db_paths);
+    }
 
     // Before create kv engine we need to check whether it needs to upgrade from v2.x to v3.x.
     // if let Err(e) = tikv::raftstore::store::maybe_upgrade_from_2_to_3(
@@ -176,6 +187,46 @@ fn run_raft_server(pd_client: RpcClient, cfg: &TiKvConfig, security_mgr: Arc<Sec
         .unwrap_or_else(|s| fatal!("failed to create kv engine: {}", s));
 
     let engines = Engines::new(Arc::new(kv_engine), Arc::new(raft_engine), cache.is_some());
+
+    // Warm up the block cache with the hot ranges persisted by the last run,
+    // so a rolling restart doesn't start with a completely cold cache.
+    let warmup_engine = engines.kv.clone();
+    thread::Builder::new()
+        .name(thd_name!("cache-warmup"))
+        .spawn(move || block_cache_warmup::warm_up_block_cache(warmup_engine))
+        .unwrap_or_else(|e| fatal!("failed to spawn cache warm-up thread: {:?}", e));
+
+    // Report the capacity and available size of every data path, so the
+    // placement across primary and secondary directories can be monitored.
+    let mut data_paths = vec![db_path.to_str().unwrap().to_owned()];
+    if !cfg.rocksdb.secondary_data_dir.is_empty() {
+        data_paths.push(cfg.rocksdb.secondary_data_dir.clone());
+    }
+    data_paths.push(cfg.raft_store.raftdb_path.clone());
+    thread::Builder::new()
+        .name(thd_name!("path-metrics"))
+        .spawn(move || loop {
+            for path in &data_paths {
+                match fs2::statvfs(path) {
+                    Ok(stats) => {
+                        DATA_PATH_SIZE_GAUGE_VEC
+                            .with_label_values(&[path, "capacity"])
+                            .set(stats.total_space() as i64);
+                        DATA_PATH_SIZE_GAUGE_VEC
+                            .with_label_values(&[path, "available"])
+                            .set(stats.free_space() as i64);
+                    }
+                    Err(e) => warn!(
+                        "get disk stat for data path failed";
+                        "path" => %path,
+                        "err" => %e
+                    ),
+                }
+            }
+            thread::sleep(DATA_PATH_METRICS_INTERVAL);
+        })
+        .unwrap_or_else(|e| fatal!("failed to spawn path metrics thread: {:?}", e));
+
     let store_meta = Arc::new(Mutex::new(StoreMeta::new(PENDING_VOTES_CAP)));
     let local_reader = LocalReader::new(engines.kv.clone(), store_meta.clone(), router.clone());
     let raft_router = ServerRaftStoreRouter::new(router.clone(), local_reader);
@@ -340,6 +391,20 @@ fn run_raft_server(pd_client: RpcClient, cfg: &TiKvConfig, security_mgr: Arc<Sec
         .start(server_cfg, security_mgr)
         .unwrap_or_else(|e| fatal!("failed to start server: {}", e));
 
+    // Start the extended stats monitor now that all thread pools are up, so
+    // it can catch their threads.
+    let mut stats_monitor = StatsMonitor::new(
+        engines.kv.clone(),
+        pd_sender,
+        Duration::from_millis(DEFAULT_STATS_MONITOR_INTERVAL),
+    );
+    if let Err(e) = stats_monitor.start() {
+        error!(
+            "failed to start stats monitor";
+            "err" => %e
+        );
+    }
+
     let server_cfg = cfg.server.clone();
     let mut status_enabled = cfg.metric.address.is_empty() && !server_cfg.status_addr.is_empty();
 
@@ -371,6 +436,8 @@ fn run_raft_server(pd_client: RpcClient, cfg: &TiKvConfig, security_mgr: Arc<Sec
 
     metrics_flusher.stop();
 
+    stats_monitor.stop();
+
     node.stop();
 
     region_info_accessor.stop();
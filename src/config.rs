@@ -639,6 +639,7 @@ pub struct DbConfig {
     pub wal_dir: String,
     pub wal_ttl_seconds: u64,
     pub wal_size_limit: ReadableSize,
+    pub recycle_log_file_num: u64,
     pub max_total_wal_size: ReadableSize,
     pub max_background_jobs: i32,
     pub max_manifest_file_size: ReadableSize,
@@ -661,6 +662,8 @@ pub struct DbConfig {
     pub writable_file_max_buffer_size: ReadableSize,
     pub use_direct_io_for_flush_and_compaction: bool,
     pub enable_pipelined_write: bool,
+    pub secondary_data_dir: String,
+    pub primary_data_target_size: ReadableSize,
     pub defaultcf: DefaultCfConfig,
     pub writecf: WriteCfConfig,
     pub lockcf: LockCfConfig,
@@ -675,6 +678,7 @@ impl Default for DbConfig {
             wal_dir: "".to_owned(),
             wal_ttl_seconds: 0,
             wal_size_limit: ReadableSize::kb(0),
+            recycle_log_file_num: 0,
             max_total_wal_size: ReadableSize::gb(4),
             max_background_jobs: 6,
             max_manifest_file_size: ReadableSize::mb(128),
@@ -696,6 +700,8 @@ impl Default for DbConfig {
             writable_file_max_buffer_size: ReadableSize::mb(1),
             use_direct_io_for_flush_and_compaction: false,
             enable_pipelined_write: true,
+            secondary_data_dir: "".to_owned(),
+            primary_data_target_size: ReadableSize::kb(0),
             defaultcf: DefaultCfConfig::default(),
             writecf: WriteCfConfig::default(),
             lockcf: LockCfConfig::default(),
@@ -714,6 +720,9 @@ impl DbConfig {
         }
         opts.set_wal_ttl_seconds(self.wal_ttl_seconds);
         opts.set_wal_size_limit_mb(self.wal_size_limit.as_mb());
+        // Reusing recycled WAL files avoids the file allocation cost on every
+        // WAL switch, which matters when the WAL lives on a low-latency device.
+        opts.set_recycle_log_file_num(self.recycle_log_file_num);
         opts.set_max_total_wal_size(self.max_total_wal_size.0);
         opts.set_max_background_jobs(self.max_background_jobs);
         opts.set_max_manifest_file_size(self.max_manifest_file_size.0);
@@ -759,6 +768,21 @@ impl DbConfig {
         opts
     }
 
+    /// Builds the `db_paths` list for the kv engine. The primary path keeps
+    /// the upper levels and fills up to `primary_data_target_size`, while the
+    /// bottommost (cold) SST files overflow to `secondary_data_dir`. Returns
+    /// an empty list when no secondary directory is configured, in which case
+    /// the engine uses its single data dir as before.
+    pub fn build_db_paths(&self, primary_dir: &str) -> Vec<(String, u64)> {
+        if self.secondary_data_dir.is_empty() {
+            return Vec::new();
+        }
+        vec![
+            (primary_dir.to_owned(), self.primary_data_target_size.0),
+            (self.secondary_data_dir.clone(), u64::max_value()),
+        ]
+    }
+
     pub fn build_cf_opts(&self, cache: &Option<Cache>) -> Vec<CFOptions<'_>> {
         vec![
             CFOptions::new(CF_DEFAULT, self.defaultcf.build_opt(cache)),
@@ -868,6 +892,7 @@ pub struct RaftDbConfig {
     pub wal_dir: String,
     pub wal_ttl_seconds: u64,
     pub wal_size_limit: ReadableSize,
+    pub recycle_log_file_num: u64,
     pub max_total_wal_size: ReadableSize,
     pub max_background_jobs: i32,
     pub max_manifest_file_size: ReadableSize,
@@ -897,6 +922,7 @@ impl Default for RaftDbConfig {
             wal_dir: "".to_owned(),
             wal_ttl_seconds: 0,
             wal_size_limit: ReadableSize::kb(0),
+            recycle_log_file_num: 0,
             max_total_wal_size: ReadableSize::gb(4),
             max_background_jobs: 4,
             max_manifest_file_size: ReadableSize::mb(20),
@@ -930,6 +956,7 @@ impl RaftDbConfig {
         }
         opts.set_wal_ttl_seconds(self.wal_ttl_seconds);
         opts.set_wal_size_limit_mb(self.wal_size_limit.as_mb());
+        opts.set_recycle_log_file_num(self.recycle_log_file_num);
         opts.set_max_background_jobs(self.max_background_jobs);
         opts.set_max_total_wal_size(self.max_total_wal_size.0);
         opts.set_max_manifest_file_size(self.max_manifest_file_size.0);
@@ -1287,6 +1314,25 @@ impl TiKvConfig {
         if !db_exist(&kv_db_path) && db_exist(&self.raft_store.raftdb_path) {
             return Err("default rocksdb not exist, buf raftdb exist".into());
         }
+        // The two engines must not write their WAL files into the same
+        // directory, otherwise their log files will be mixed up.
+        if !self.rocksdb.wal_dir.is_empty() && self.rocksdb.wal_dir == self.raftdb.wal_dir {
+            return Err("raftdb.wal_dir can not same with rocksdb.wal_dir".into());
+        }
+        if !self.rocksdb.secondary_data_dir.is_empty() {
+            self.rocksdb.secondary_data_dir =
+                config::canonicalize_path(&self.rocksdb.secondary_data_dir)?;
+            if self.rocksdb.secondary_data_dir == kv_db_path {
+                return Err(
+                    "rocksdb.secondary_data_dir can not same with storage.data_dir/db".into(),
+                );
+            }
+            if self.rocksdb.primary_data_target_size.0 == 0 {
+                return Err("rocksdb.primary_data_target_size must be set when \
+                            rocksdb.secondary_data_dir is configured"
+                    .into());
+            }
+        }
 
         let expect_keepalive = self.raft_store.raft_heartbeat_interval() * 2;
         if expect_keepalive > self.server.grpc_keepalive_time.0 {
@@ -1441,6 +1487,15 @@ impl TiKvConfig {
             ));
         }
 
+        if last_cfg.rocksdb.secondary_data_dir != self.rocksdb.secondary_data_dir {
+            return Err(format!(
+                "db secondary_data_dir have been changed, former secondary_data_dir \
+                 is '{}', current secondary_data_dir is '{}', please guarantee all \
+                 SST files have been moved to destination directory.",
+                last_cfg.rocksdb.secondary_data_dir, self.rocksdb.secondary_data_dir
+            ));
+        }
+
         Ok(())
     }
 
@@ -8,7 +8,7 @@ use cop_datatype::{FieldTypeFlag, FieldTypeTp};
 use super::{Error, Result};
 use crate::coprocessor::codec::mysql::decimal::DECIMAL_STRUCT_SIZE;
 use crate::coprocessor::codec::mysql::{
-    Decimal, DecimalEncoder, Duration, DurationEncoder, Json, JsonEncoder, Time, TimeEncoder,
+    Decimal, DecimalEncoder, Duration, Json, JsonEncoder, Time, TimeEncoder,
 };
 use crate::coprocessor::codec::Datum;
 
@@ -43,10 +43,12 @@ impl Column {
                 //TODO:no Datum::F32
                 Column::new_fixed_len(8, init_cap)
             }
-            FieldTypeTp::Duration
-            | FieldTypeTp::Date
-            | FieldTypeTp::DateTime
-            | FieldTypeTp::Timestamp => Column::new_fixed_len(16, init_cap),
+            // A duration cell is the packed `Duration` bitfield (which
+            // already carries the fsp) in a single u64.
+            FieldTypeTp::Duration => Column::new_fixed_len(8, init_cap),
+            FieldTypeTp::Date | FieldTypeTp::DateTime | FieldTypeTp::Timestamp => {
+                Column::new_fixed_len(16, init_cap)
+            }
             FieldTypeTp::NewDecimal => Column::new_fixed_len(DECIMAL_STRUCT_SIZE, init_cap),
             _ => Column::new_var_len_column(init_cap),
         }
@@ -279,9 +281,10 @@ impl Column {
         Time::decode(&mut data)
     }
 
-    /// Append a duration datum to the column.
+    /// Append a duration datum to the column, stored as the packed
+    /// `Duration` bits (value plus fsp) in a little-endian u64.
     pub fn append_duration(&mut self, d: Duration) -> Result<()> {
-        self.data.encode_duration(d)?;
+        self.data.encode_u64_le(d.to_bits())?;
         self.finish_append_fixed()
     }
 
@@ -290,7 +293,7 @@ impl Column {
         let start = idx * self.fixed_len;
         let end = start + self.fixed_len;
         let mut data = &self.data[start..end];
-        Duration::decode(&mut data)
+        Duration::from_bits(number::decode_u64_le(&mut data)?)
     }
 
     /// Append a decimal datum to the column.
@@ -480,7 +483,12 @@ mod tests {
     fn test_column_duration() {
         let fields = vec![field_type(FieldTypeTp::Duration)];
         let duration = Duration::parse(b"10:11:12", 0).unwrap();
-        let data = vec![Datum::Null, Datum::Dur(duration)];
+        let data = vec![
+            Datum::Null,
+            Datum::Dur(duration),
+            Datum::Dur(Duration::parse(b"-838:59:59.999999", 6).unwrap()),
+            Datum::Dur(Duration::parse(b"00:00:00.5", 1).unwrap()),
+        ];
         test_colum_datum(fields, data);
     }
 
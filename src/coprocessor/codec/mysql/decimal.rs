@@ -1481,6 +1481,11 @@ impl Decimal {
         res
     }
 
+    /// `frac_cnt` returns the number of decimal digits after the point.
+    pub fn frac_cnt(&self) -> u8 {
+        self.frac_cnt
+    }
+
     /// `as_i64` returns int part of the decimal.
     pub fn as_i64(&self) -> Res<i64> {
         let mut x = 0i64;
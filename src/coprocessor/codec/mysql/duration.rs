@@ -9,10 +9,11 @@ use tikv_util::codec::number::{self, NumberEncoder};
 use tikv_util::codec::BytesSlice;
 
 use super::super::{Result, TEN_POW};
-use super::MAX_FSP;
-use super::{check_fsp, Decimal};
+use super::{MAX_FSP, MIN_FSP, UNSPECIFIED_FSP};
+use super::{check_fsp, Decimal, Res, Time, TimeType};
 
 use bitfield::bitfield;
+use smallvec::SmallVec;
 
 pub const NANOS_PER_SEC: i64 = 1_000_000_000;
 pub const MICROS_PER_SEC: i64 = 1_000_000;
@@ -23,9 +24,11 @@ const SECS_PER_HOUR: u32 = 3600;
 const SECS_PER_MINUTE: u32 = 60;
 
 const MAX_HOURS: u32 = 838;
+/// Longest input `Duration::parse` accepts; see `parse_with_max_len`.
+pub const MAX_DURATION_STR_LEN: usize = 64;
 const MAX_MINUTES: u32 = 59;
 const MAX_SECONDS: u32 = 59;
-const MAX_MICROS: u32 = 999_999;
+const MAX_NANOS: u32 = 999_999_999;
 
 #[inline]
 fn check_hour(hour: u32) -> Result<u32> {
@@ -67,20 +70,20 @@ fn check_second(second: u32) -> Result<u32> {
 }
 
 #[inline]
-fn check_micros(micros: u32) -> Result<u32> {
-    if micros > MAX_MICROS {
+fn check_nanos(nanos: u32) -> Result<u32> {
+    if nanos > MAX_NANOS {
         Err(invalid_type!(
             "invalid fractional value: {} larger than {}",
-            micros,
-            MAX_MICROS
+            nanos,
+            MAX_NANOS
         ))
     } else {
-        Ok(micros)
+        Ok(nanos)
     }
 }
 
 mod parser {
-    use super::{check_hour, check_minute, check_second, MICRO_WIDTH, TEN_POW};
+    use super::{check_minute, check_second, NANO_WIDTH, TEN_POW};
     use nom::character::complete::{digit1, multispace0, multispace1};
     use nom::{
         alt, call, char, complete, cond, do_parse, eof, map, map_res, opt, peek, preceded, tag,
@@ -113,50 +116,47 @@ mod parser {
         })
     }
 
-    /// Extracts a `u32` with length `fsp` from a buffer which matches pattern: `\d+.*`
-    /// This function assumes that `fsp` is valid
+    /// Extracts the fractional part from a buffer which matches pattern: `\d+.*`
     ///
     /// ```compile_fail
-    /// assert_eq!(read_int_with_fsp(b"1234567", 3), Ok(b"", 123400000));
-    /// assert_eq!(read_int_with_fsp(b"1234", 6), Ok(b"", 123400000));
+    /// assert_eq!(read_fraction(b"1234"), Ok(b"", 123400000));
+    /// assert_eq!(read_fraction(b"123456789"), Ok(b"", 123456789));
     /// ```
     ///
     /// NOTE:
-    /// 1. The behavior of this function is similar to `read_int` except that it's designed to read the
-    /// fractional part of a `TIME`
-    /// 2. The fractional part will be align to a 9-digit number which it's easy to round with `fsp`
-    ///
-    /// FIXME: the fraction should not be round, it's incompatible with MySQL.
-    fn read_int_with_fsp(input: &[u8], fsp: u8) -> IResult<&[u8], u32> {
+    /// The fraction is aligned to `NANO_WIDTH` digits, which keeps every input
+    /// digit that can influence the half-up decision at any valid fsp; the
+    /// parser itself never rounds, `round` does that exactly once with the
+    /// target fsp. Digits past `NANO_WIDTH` cannot change the result and are
+    /// dropped.
+    fn read_fraction(input: &[u8]) -> IResult<&[u8], u32> {
         map!(input, digit1, |buf: &[u8]| -> u32 {
-            let fsp = usize::from(fsp);
-            let (fraction, len) = if fsp >= buf.len() {
-                (buf_to_int(buf), buf.len())
-            } else {
-                (buf_to_int(&buf[..=fsp]), fsp + 1)
-            };
-            fraction * TEN_POW[MICRO_WIDTH.checked_sub(len).unwrap_or(0)]
+            let len = buf.len().min(NANO_WIDTH);
+            buf_to_int(&buf[..len]) * TEN_POW[NANO_WIDTH - len]
         })
     }
 
-    /// Parse the sign of `Duration`, return true if it's negative otherwise false
+    /// Parse the sign of `Duration`, return true if it's negative otherwise false.
+    /// An explicit `+` is accepted as a no-op positive sign.
     ///
     /// ```compile_fail
     /// assert_eq!(neg(b"- .123"),  Ok(b".123", true));
     /// assert_eq!(neg(b"-.123"),   Ok(b".123", true));
     /// assert_eq!(neg(b"- 11:21"), Ok(b"11:21", true));
     /// assert_eq!(neg(b"-11:21"),  Ok(b"11:21", true));
+    /// assert_eq!(neg(b"+11:21"),  Ok(b"11:21", false));
     /// assert_eq!(neg(b"11:21"),   Ok(b"11:21", false));
     /// ```
     fn neg(input: &[u8]) -> IResult<&[u8], bool> {
         do_parse!(
             input,
-            neg: map!(opt!(complete!(char!('-'))), |flag| flag.is_some())
-                >> preceded!(
-                    multispace0,
-                    alt!(complete!(peek!(call!(digit1))) | complete!(peek!(tag!("."))))
-                )
-                >> (neg)
+            neg: map!(
+                opt!(complete!(alt!(complete!(char!('-')) | complete!(char!('+'))))),
+                |sign| sign == Some('-')
+            ) >> preceded!(
+                multispace0,
+                alt!(complete!(peek!(call!(digit1))) | complete!(peek!(tag!("."))))
+            ) >> (neg)
         )
     }
 
@@ -214,7 +214,7 @@ mod parser {
     fn hhmmss(input: &[u8]) -> IResult<&[u8], [Option<u32>; 3]> {
         do_parse!(
             input,
-            hour: opt!(map_res!(read_int, check_hour))
+            hour: opt!(read_int)
                 >> has_mintue: separator
                 >> minute: cond!(has_mintue, map_res!(read_int, check_minute))
                 >> has_second: separator
@@ -226,29 +226,29 @@ mod parser {
     /// Parse fractional part.
     ///
     /// ```compile_fail
-    /// assert_eq!(fraction(" .123", 3), Ok(b"", Some(123)));
-    /// assert_eq!(fraction("123", 3), Ok(b"", None));
+    /// assert_eq!(fraction(" .123"), Ok(b"", Some(123000000)));
+    /// assert_eq!(fraction("123"), Ok(b"", None));
     /// ```
-    fn fraction(input: &[u8], fsp: u8) -> IResult<&[u8], Option<u32>> {
+    fn fraction(input: &[u8]) -> IResult<&[u8], Option<u32>> {
         do_parse!(
             input,
             multispace0
                 >> opt!(complete!(char!('.')))
-                >> fraction: opt!(call!(read_int_with_fsp, fsp))
+                >> fraction: opt!(read_fraction)
                 >> multispace0
                 >> (fraction)
         )
     }
 
     /// Parse `Duration`
-    pub fn parse(input: &[u8], fsp: u8) -> IResult<&[u8], (bool, [Option<u32>; 5])> {
+    pub fn parse(input: &[u8]) -> IResult<&[u8], (bool, [Option<u32>; 5])> {
         do_parse!(
             input,
             multispace0
                 >> neg: neg
                 >> day: day
                 >> hhmmss: hhmmss
-                >> fraction: call!(fraction, fsp)
+                >> fraction: fraction
                 >> eof!()
                 >> (neg, [day, hhmmss[0], hhmmss[1], hhmmss[2], fraction])
         )
@@ -265,41 +265,38 @@ bitfield! {
     #[inline]
     bool, get_reserved, set_reserved: 62;
     #[inline]
-    u32, get_hours, set_hours: 61, 48;
+    u32, get_hours, set_hours: 61, 52;
     #[inline]
-    u32, get_minutes, set_minutes: 47, 40;
+    u32, get_minutes, set_minutes: 51, 46;
     #[inline]
-    u32, get_secs, set_secs: 39, 32;
+    u32, get_secs, set_secs: 45, 40;
     #[inline]
-    u32, get_micros, set_micros: 31, 8;
+    u32, get_nanos, set_nanos: 39, 8;
     #[inline]
     u8, get_fsp, set_fsp: 7, 0;
 }
 
-/// Rounds `micros` with `fsp` and handles the carry.
+/// Rounds `nanos` with `fsp` and handles the carry.
+///
+/// NOTE: `nanos` is expected to be aligned to `NANO_WIDTH` digits, so that
+/// the half-up decision is made on the full input precision; the rounded
+/// result is written back with the same alignment, holding no significant
+/// digits beyond `fsp`.
 #[inline]
 fn round(
     hours: &mut u32,
     minutes: &mut u32,
     secs: &mut u32,
-    micros: &mut u32,
+    nanos: &mut u32,
     fsp: u8,
 ) -> Result<()> {
-    if *micros < 1_000_000 {
-        *micros *= 10;
-    }
-
     let fsp = usize::from(fsp);
 
-    *micros = if fsp == MICRO_WIDTH {
-        (*micros + 5) / 10
-    } else {
-        let mask = TEN_POW[MICRO_WIDTH - fsp];
-        (*micros / mask + 5) / 10 * mask
-    };
+    let mask = TEN_POW[NANO_WIDTH - fsp];
+    *nanos = (*nanos / (mask / 10) + 5) / 10 * mask;
 
-    if *micros >= 1_000_000 {
-        *micros -= 1_000_000;
+    if *nanos >= NANOS_PER_SEC as u32 {
+        *nanos -= NANOS_PER_SEC as u32;
         *secs += 1;
         if *secs >= 60 {
             *secs -= 60;
@@ -322,11 +319,81 @@ impl Duration {
         self.0
     }
 
+    /// Returns the fsp-normalized bits as big-endian bytes for feeding a
+    /// byte-oriented streaming hasher (e.g. a partition key). Consistent
+    /// with the `Hash`/`Eq` contract: values that compare equal (fsp aside)
+    /// produce identical bytes.
+    #[inline]
+    pub fn hash_bytes(self) -> [u8; 8] {
+        self.to_bits_fsp0().to_be_bytes()
+    }
+
+    /// Transforms the fsp-normalized bits so that plain `u64` (and thus
+    /// lexicographic byte) ordering matches `Ord`: positives get the high
+    /// bit set, negatives are complemented so larger magnitudes sort lower.
+    /// Usable directly as an index key without the two-i64 memcmp codec.
+    #[inline]
+    pub fn to_sort_key(self) -> u64 {
+        let bits = self.to_bits_fsp0();
+        if self.get_neg() {
+            !bits
+        } else {
+            bits | (1 << 63)
+        }
+    }
+
+    /// Like `to_bits`, but with the fsp field zeroed (the value itself is
+    /// unchanged). For storage that keeps the fsp in column metadata rather
+    /// than per row; the decode side restores it from the schema.
+    #[inline]
+    pub fn to_bits_fsp0(mut self) -> u64 {
+        self.set_fsp(0);
+        self.0
+    }
+
     #[inline]
     pub fn hours(self) -> u32 {
         self.get_hours()
     }
 
+    /// Returns the signed total whole hours. Reads the hour field off the
+    /// bitfield directly rather than dividing a micros total, so it costs
+    /// the same as `hours()` even for day-heavy values; `bench_total_hours`
+    /// keeps that honest.
+    #[inline]
+    pub fn total_hours(self) -> i32 {
+        let hours = self.hours() as i32;
+        if self.get_neg() {
+            -hours
+        } else {
+            hours
+        }
+    }
+
+    /// Splits the value into signed whole days and the remaining
+    /// time-of-day part (which keeps the sign): `-50:30:00` splits into
+    /// `(-2, -02:30:00)`. The day division is by a constant, so this stays
+    /// branch- and division-free after codegen.
+    #[inline]
+    pub fn split_days(self) -> (i32, Duration) {
+        let days = (self.hours() / 24) as i32;
+        let mut rem = self;
+        rem.set_hours(self.hours() % 24);
+        if rem.is_zero() {
+            rem.set_neg(false);
+        }
+
+        (if self.get_neg() { -days } else { days }, rem)
+    }
+
+    /// Returns the hour part folded to a wall-clock time of day, i.e.
+    /// `hours() % 24`. Used by the `%h`/`%I` format specifiers and when
+    /// converting to a `Time` component.
+    #[inline]
+    pub fn hours_of_day(self) -> u32 {
+        self.hours() % 24
+    }
+
     #[inline]
     pub fn minutes(self) -> u32 {
         self.get_minutes()
@@ -337,9 +404,19 @@ impl Duration {
         self.get_secs()
     }
 
+    /// Returns the fractional part truncated to whole microseconds. Values
+    /// built through the public constructors never carry sub-microsecond
+    /// digits past their fsp, so the truncation is lossless for them.
     #[inline]
     pub fn micros(self) -> u32 {
-        self.get_micros()
+        self.get_nanos() / 1000
+    }
+
+    /// Returns the fractional part in whole nanoseconds, the full stored
+    /// precision.
+    #[inline]
+    pub fn subsec_nanos(self) -> u32 {
+        self.get_nanos()
     }
 
     #[inline]
@@ -357,7 +434,7 @@ impl Duration {
     pub fn from_bits(v: u64) -> Result<Duration> {
         let mut duration = Duration(v);
 
-        check_micros(duration.micros())?;
+        check_nanos(duration.subsec_nanos())?;
         check_second(duration.secs())?;
         check_minute(duration.minutes())?;
         check_hour(duration.hours())?;
@@ -366,6 +443,59 @@ impl Duration {
         Ok(duration)
     }
 
+    /// Returns whether the stored fraction carries no significant digits
+    /// beyond `fsp`, i.e. storing the value at `fsp` would be lossless. Backs
+    /// pre-insert checks in strict mode; values of `fsp` past `NANO_WIDTH`
+    /// trivially fit.
+    #[inline]
+    pub fn fits_fsp(self, fsp: u8) -> bool {
+        let fsp = usize::from(fsp).min(NANO_WIDTH);
+        self.subsec_nanos() % TEN_POW[NANO_WIDTH - fsp] == 0
+    }
+
+    /// Puts the value into canonical form: clears the reserved bit, clamps
+    /// the fsp into range, truncates fraction digits the fsp cannot hold, and
+    /// drops a negative sign off zero. Fields that are outright out of
+    /// range (e.g. crafted hours past 838) are not repaired — `from_bits`
+    /// is the gate for those.
+    pub fn normalize(mut self) -> Duration {
+        self.set_reserved(false);
+        if self.fsp() > MAX_FSP as u8 {
+            self.set_fsp(MAX_FSP as u8);
+        }
+
+        let granularity = TEN_POW[NANO_WIDTH - usize::from(self.fsp())];
+        self.set_nanos(self.subsec_nanos() / granularity * granularity);
+
+        if self.is_zero() {
+            self.set_neg(false);
+        }
+        self
+    }
+
+    /// Returns true when the value is in canonical form: the reserved bit is
+    /// clear, the fsp is in range, the fields are within their domains,
+    /// there is no negative zero, and the fraction carries no significant digits
+    /// beyond what the fsp can represent. Values built by `parse`/
+    /// `from_micros` are always canonical; crafted bit patterns may not be.
+    pub fn is_canonical(self) -> bool {
+        if self.get_reserved()
+            || self.fsp() > MAX_FSP as u8
+            || check_hour(self.hours()).is_err()
+            || check_minute(self.minutes()).is_err()
+            || check_second(self.secs()).is_err()
+            || check_nanos(self.subsec_nanos()).is_err()
+        {
+            return false;
+        }
+
+        if self.get_neg() && self.is_zero() {
+            return false;
+        }
+
+        self.fits_fsp(self.fsp())
+    }
+
     /// Returns the identity element of `Duration`
     pub fn zero() -> Duration {
         Duration(0)
@@ -389,6 +519,28 @@ impl Duration {
         self.micros()
     }
 
+    /// Returns only the sub-second part as a `Duration`, preserving the sign
+    /// and fsp. `fract() + trunc()` reconstructs the original value.
+    pub fn fract(mut self) -> Duration {
+        self.set_hours(0);
+        self.set_minutes(0);
+        self.set_secs(0);
+        if self.subsec_nanos() == 0 {
+            self.set_neg(false);
+        }
+        self
+    }
+
+    /// Returns only the whole-second part as a `Duration`, preserving the
+    /// sign and fsp. `fract() + trunc()` reconstructs the original value.
+    pub fn trunc(mut self) -> Duration {
+        self.set_nanos(0);
+        if self.is_zero() {
+            self.set_neg(false);
+        }
+        self
+    }
+
     /// Returns the number of whole seconds contained by this Duration.
     pub fn to_secs(self) -> i32 {
         let secs =
@@ -405,17 +557,110 @@ impl Duration {
     /// The returned value does include the fractional (nanosecond) part of the duration.
     pub fn to_secs_f64(self) -> f64 {
         let secs = f64::from(self.to_secs());
-        let micros = f64::from(self.subsec_micros()) * 1e-6;
+        let nanos = f64::from(self.subsec_nanos()) * 1e-9;
 
-        secs + if self.get_neg() { -micros } else { micros }
+        secs + if self.get_neg() { -nanos } else { nanos }
     }
 
     /// Returns the `Duration` in whole nanoseconds
     pub fn to_nanos(self) -> i64 {
         let secs = i64::from(self.to_secs()) * NANOS_PER_SEC;
-        let micros = i64::from(self.subsec_micros());
+        let nanos = i64::from(self.subsec_nanos());
+
+        secs + if self.get_neg() { -nanos } else { nanos }
+    }
+
+    /// Returns the signed total seconds with the sub-second part rounded
+    /// half-up on the magnitude (`00:00:00.6` is `1`, `-00:00:00.6` is
+    /// `-1`), distinct from the truncating `to_secs`.
+    pub fn total_seconds_rounded(self) -> i64 {
+        let secs = i64::from(self.to_secs()).abs()
+            + if self.subsec_nanos() >= NANOS_PER_SEC as u32 / 2 {
+                1
+            } else {
+                0
+            };
+
+        if self.get_neg() {
+            -secs
+        } else {
+            secs
+        }
+    }
+
+    /// Returns the signed total milliseconds including the sub-millisecond
+    /// fraction (`00:00:00.0005` is `0.5`), the unit metrics exporters
+    /// expect. Distinct from truncating integer conversions.
+    pub fn to_millis_f64(self) -> f64 {
+        self.to_nanos() as f64 / 1e6
+    }
+
+    /// Returns the `Duration` in whole microseconds, using saturating
+    /// arithmetic throughout so that even a `Duration` crafted from untrusted
+    /// bits (bypassing `from_bits` validation) cannot overflow the multiply.
+    /// The bitfield widths make the saturated bounds unreachable in practice.
+    pub fn as_micros_saturating(self) -> i64 {
+        let secs = i64::from(self.hours())
+            .saturating_mul(i64::from(SECS_PER_HOUR))
+            .saturating_add(i64::from(self.minutes()).saturating_mul(i64::from(SECS_PER_MINUTE)))
+            .saturating_add(i64::from(self.secs()));
+        let micros = secs
+            .saturating_mul(MICROS_PER_SEC)
+            .saturating_add(i64::from(self.micros()));
+
+        if self.get_neg() {
+            -micros
+        } else {
+            micros
+        }
+    }
+
+    /// Packs the `Duration` into a fixed-width layout: signed micros as a
+    /// little-endian `i64` (8 bytes) followed by the fsp (1 byte). Unlike the
+    /// varint codec this is a constant 9 bytes per value, which suits mmap'd
+    /// columnar storage where random access needs a fixed stride.
+    pub fn to_fixed_bytes(self) -> [u8; 9] {
+        let micros = self.to_nanos() / 1000;
+        let mut buf = [0; 9];
+        buf[..8].copy_from_slice(&micros.to_le_bytes());
+        buf[8] = self.fsp();
+        buf
+    }
+
+    /// Decodes a `Duration` previously packed with `to_fixed_bytes`,
+    /// rejecting an out-of-range fsp byte.
+    pub fn from_fixed_bytes(buf: &[u8; 9]) -> Result<Duration> {
+        let mut micros = [0; 8];
+        micros.copy_from_slice(&buf[..8]);
+        Duration::from_micros(i64::from_le_bytes(micros), buf[8] as i8)
+    }
+
+    /// Returns the signed total minutes with the seconds (and fraction)
+    /// rounded half-up to the nearest minute, for coarse reporting:
+    /// `12:00:29` is `720`, `12:00:30` rounds to `721`. Halves round away
+    /// from zero, so `-12:00:30` is `-721`.
+    pub fn rounded_minutes(self) -> i64 {
+        const MINUTE_MICROS: i64 = 60 * MICROS_PER_SEC;
 
-        secs + if self.get_neg() { -micros } else { micros } * 1000
+        let micros = self.to_nanos().abs() / 1000;
+        let minutes = (micros + MINUTE_MICROS / 2) / MINUTE_MICROS;
+
+        if self.get_neg() {
+            -minutes
+        } else {
+            minutes
+        }
+    }
+
+    /// Splits the `Duration` into signed whole seconds and a signed
+    /// sub-second nanosecond part, both carrying the sign — the `timespec`
+    /// convention, where `-00:00:00.5` is `(0, -500_000_000)`. Distinct from
+    /// `subsec_nanos`, which returns the unsigned magnitude.
+    pub fn to_signed_secs_and_nanos(self) -> (i64, i32) {
+        let secs = i64::from(self.to_secs());
+        let nanos = self.subsec_nanos() as i32;
+
+        (secs, if self.get_neg() { -nanos } else { nanos })
     }
 
     /// Constructs a `Duration` from `nanos` with `fsp`
@@ -425,17 +670,58 @@ impl Duration {
 
     pub fn from_micros(micros: i64, fsp: i8) -> Result<Duration> {
         let fsp = check_fsp(fsp)?;
+        Duration::from_micros_unchecked_fsp(micros, fsp)
+    }
+
+    /// Constructs a `Duration` from micros at fsp 0, skipping the `check_fsp`
+    /// call for hot codec paths where the fsp is a known-valid constant.
+    pub fn from_micros_0(micros: i64) -> Result<Duration> {
+        Duration::from_micros_unchecked_fsp(micros, 0)
+    }
+
+    /// Like `from_micros_0` at fsp 3.
+    pub fn from_micros_3(micros: i64) -> Result<Duration> {
+        Duration::from_micros_unchecked_fsp(micros, 3)
+    }
+
+    /// Like `from_micros_0` at fsp 6.
+    pub fn from_micros_6(micros: i64) -> Result<Duration> {
+        Duration::from_micros_unchecked_fsp(micros, 6)
+    }
+
+    /// `from_micros` past fsp validation; `fsp` must already be in range.
+    fn from_micros_unchecked_fsp(micros: i64, fsp: u8) -> Result<Duration> {
         let neg = micros < 0;
 
         let secs = (micros / MICROS_PER_SEC).abs();
-        let mut micros = (micros % MICROS_PER_SEC).abs() as u32;
+        let mut nanos = (micros % MICROS_PER_SEC).abs() as u32 * 1000;
 
         let mut hours = (secs / i64::from(SECS_PER_HOUR)) as u32;
         let mut minutes = (secs % i64::from(SECS_PER_HOUR) / i64::from(SECS_PER_MINUTE)) as u32;
         let mut secs = (secs % 60) as u32;
 
-        round(&mut hours, &mut minutes, &mut secs, &mut micros, fsp)?;
-        Ok(Duration::new(neg, hours, minutes, secs, micros, fsp))
+        round(&mut hours, &mut minutes, &mut secs, &mut nanos, fsp)?;
+        Ok(Duration::new(neg, hours, minutes, secs, nanos, fsp))
+    }
+
+    /// Like `from_micros`, additionally reporting whether reducing to `fsp`
+    /// changed the value versus a full-precision conversion, so data-quality
+    /// tooling can quantify precision loss on ingest.
+    pub fn from_micros_reporting(micros: i64, fsp: i8) -> Result<(Duration, bool)> {
+        let rounded = Duration::from_micros(micros, fsp)?;
+        let full = Duration::from_micros(micros, MAX_FSP)?;
+        Ok((rounded, rounded.to_nanos() != full.to_nanos()))
+    }
+
+    /// Constructs a `Duration` from plain whole seconds, the integer entry
+    /// point `SEC_TO_TIME` uses in strict mode; out-of-range seconds are an
+    /// error.
+    pub fn from_secs(secs: i64, fsp: i8) -> Result<Duration> {
+        Duration::from_micros(
+            secs.checked_mul(MICROS_PER_SEC)
+                .ok_or(invalid_type!("micros overflow"))?,
+            fsp,
+        )
     }
 
     pub fn from_millis(millis: i64, fsp: i8) -> Result<Duration> {
@@ -448,14 +734,14 @@ impl Duration {
     }
 
     /// Constructs a `Duration` from with details without validation
-    fn new(neg: bool, hours: u32, minutes: u32, secs: u32, micros: u32, fsp: u8) -> Duration {
+    fn new(neg: bool, hours: u32, minutes: u32, secs: u32, nanos: u32, fsp: u8) -> Duration {
         let mut duration = Duration(0);
 
         duration.set_neg(neg);
         duration.set_hours(hours);
         duration.set_minutes(minutes);
         duration.set_secs(secs);
-        duration.set_micros(micros);
+        duration.set_nanos(nanos);
         duration.set_fsp(fsp);
 
         duration
@@ -465,14 +751,39 @@ impl Duration {
     /// returns the duration type `Time` value.
     /// See: http://dev.mysql.com/doc/refman/5.7/en/fractional-seconds.html
     pub fn parse(input: &[u8], fsp: i8) -> Result<Duration> {
+        Duration::parse_with_max_len(input, fsp, MAX_DURATION_STR_LEN)
+    }
+
+    /// `parse` with a configurable input length cap. The integer fields are
+    /// already capped at 7 digits each, but without an overall bound a
+    /// pathological multi-kilobyte string still runs the full grammar; the
+    /// guard rejects over-long input up front with a specific error.
+    pub fn parse_with_max_len(input: &[u8], fsp: i8, max_len: usize) -> Result<Duration> {
         if input.is_empty() {
             return Err(invalid_type!("invalid time format"));
         }
 
+        if input.len() > max_len {
+            return Err(invalid_type!(
+                "time string too long: {} bytes (max {})",
+                input.len(),
+                max_len
+            ));
+        }
+
+        // A `-` following leading digits means the input is a `-`-separated
+        // date (`2011-11-11 ...`), a common mistake that deserves a clearer
+        // message than the generic format error.
+        if let Some(pos) = input.iter().position(|&c| c == b'-') {
+            if pos > 0 && input[..pos].iter().all(|c| c.is_ascii_digit()) {
+                return Err(invalid_type!("date literal not valid as TIME"));
+            }
+        }
+
         let fsp = check_fsp(fsp)?;
 
-        let (mut neg, [mut day, mut hour, mut minute, mut second, micros]) =
-            self::parser::parse(input, fsp)
+        let (mut neg, [mut day, mut hour, mut minute, mut second, nanos]) =
+            self::parser::parse(input)
                 .map_err(|_| invalid_type!("invalid time format"))?
                 .1;
 
@@ -483,512 +794,3839 @@ impl Duration {
             second = Some(block % 100);
         }
 
-        let (mut hour, mut minute, mut second, mut micros) = (
+        if let Some(day) = day {
+            if day * 24 + hour.unwrap_or(0) > MAX_HOURS {
+                return Err(invalid_type!("day value {} out of range", day));
+            }
+        }
+
+        let (mut hour, mut minute, mut second, mut nanos) = (
             hour.unwrap_or(0) + day.unwrap_or(0) * 24,
             minute.unwrap_or(0),
             second.unwrap_or(0),
-            micros.unwrap_or(0),
+            nanos.unwrap_or(0),
         );
 
-        if hour == 0 && minute == 0 && second == 0 && micros == 0 {
+        if hour == 0 && minute == 0 && second == 0 && nanos == 0 {
             neg = false;
         }
 
-        round(&mut hour, &mut minute, &mut second, &mut micros, fsp)?;
-        Ok(Duration::new(neg, hour, minute, second, micros, fsp))
+        round(&mut hour, &mut minute, &mut second, &mut nanos, fsp)?;
+        Ok(Duration::new(neg, hour, minute, second, nanos, fsp))
     }
 
-    /// Rounds fractional seconds precision with new FSP and returns a new one.
-    /// We will use the “round half up” rule, e.g, >= 0.5 -> 1, < 0.5 -> 0,
-    /// so 10:10:10.999999 round with fsp: 1 -> 10:10:11.0
-    /// and 10:10:10.000000 round with fsp: 0 -> 10:10:11
-    pub fn round_frac(mut self, fsp: i8) -> Result<Self> {
-        let fsp = check_fsp(fsp)?;
+    /// `parse` followed by `normalize`, the recommended entry point for
+    /// values that will be hashed or compared. `parse` alone can let a
+    /// negative zero slip through when a negative sub-fsp fraction rounds
+    /// away (`"-00:00:00.4"` at fsp 0); this closes that gap.
+    pub fn parse_canonical(input: &[u8], fsp: i8) -> Result<Duration> {
+        Ok(Duration::parse(input, fsp)?.normalize())
+    }
 
-        if fsp >= self.fsp() {
-            self.set_fsp(fsp);
-            return Ok(self);
+    /// The single entry point unifying the overflow behaviors of the parse
+    /// variants: `Error` is `parse` unchanged, `Saturate` clamps an
+    /// out-of-range value to the signed max, and `WrapDays` folds the
+    /// overflowing hours modulo 24 back into range (`"900:00:00"` becomes
+    /// `12:00:00`). Malformed input is an error under every policy.
+    pub fn parse_with_policy(
+        input: &[u8],
+        fsp: i8,
+        policy: OverflowPolicy,
+    ) -> Result<Duration> {
+        if let OverflowPolicy::Error = policy {
+            return Duration::parse(input, fsp);
         }
 
-        let mut hours = self.hours();
-        let mut minutes = self.minutes();
-        let mut secs = self.secs();
-        let mut micros = self.micros();
+        if input.is_empty() {
+            return Err(invalid_type!("invalid time format"));
+        }
+        let fsp = check_fsp(fsp)?;
 
-        round(&mut hours, &mut minutes, &mut secs, &mut micros, fsp)?;
+        let (mut neg, [mut day, mut hour, mut minute, mut second, nanos]) =
+            self::parser::parse(input)
+                .map_err(|_| invalid_type!("invalid time format"))?
+                .1;
 
-        Ok(Duration::new(
-            self.get_neg(),
-            hours,
-            minutes,
-            secs,
-            micros,
-            fsp,
-        ))
-    }
+        if day.is_some() && hour.is_none() {
+            let block = day.take().unwrap();
+            hour = Some(block / 10_000);
+            minute = Some(block / 100 % 100);
+            second = Some(block % 100);
+        }
 
-    /// Checked duration addition. Computes self + rhs, returning None if overflow occurred.
-    pub fn checked_add(self, rhs: Duration) -> Option<Duration> {
-        match (self.get_neg(), rhs.get_neg()) {
-            (false, true) => self.checked_sub(rhs.abs()),
-            (true, false) => rhs.checked_sub(self.abs()),
-            (true, true) => self.abs().checked_add(rhs.abs()).map(|mut res| {
-                res.set_neg(true);
-                res
-            }),
-            (false, false) => {
-                let mut micros = self.micros() + rhs.micros();
-                let mut secs = self.secs() + rhs.secs();
-                let mut minutes = self.minutes() + rhs.minutes();
-                let mut hours = self.hours() + rhs.hours();
+        let (mut hour, mut minute, mut second, mut nanos) = (
+            hour.unwrap_or(0) + day.unwrap_or(0) * 24,
+            minute.unwrap_or(0),
+            second.unwrap_or(0),
+            nanos.unwrap_or(0),
+        );
 
-                if i64::from(micros) >= MICROS_PER_SEC {
-                    micros -= MICROS_PER_SEC as u32;
-                    secs += 1;
-                }
-                if secs >= 60 {
-                    secs -= 60;
-                    minutes += 1;
-                }
-                if minutes >= 60 {
-                    minutes -= 60;
-                    hours += 1;
+        if hour == 0 && minute == 0 && second == 0 && nanos == 0 {
+            neg = false;
+        }
+
+        if let OverflowPolicy::WrapDays = policy {
+            if hour > MAX_HOURS {
+                hour %= 24;
+            }
+        }
+
+        if round(&mut hour, &mut minute, &mut second, &mut nanos, fsp).is_err() {
+            return Ok(match policy {
+                OverflowPolicy::Saturate => Duration::saturate(neg, fsp),
+                // a rounding carry on `838:59:59.x` can still leave range;
+                // fold it like any other overflow
+                OverflowPolicy::WrapDays => {
+                    hour %= 24;
+                    Duration::new(neg, hour, minute, second, nanos, fsp)
                 }
+                OverflowPolicy::Error => unreachable!(),
+            });
+        }
+        Ok(Duration::new(neg, hour, minute, second, nanos, fsp))
+    }
 
-                check_hour(hours).ok()?;
+    /// The single-call `CAST(str AS TIME(n))` flow: parses at full
+    /// precision and rounds to `target_fsp` once, so the rounding decision
+    /// sees every input digit. Parsing at `input_fsp` first and rounding
+    /// after would round twice — `".46"` through fsp 1 becomes `.5` and
+    /// then `00:00:01` at fsp 0, where a single rounding yields `00:00:00`.
+    /// `input_fsp` is still validated so invalid column metadata surfaces.
+    pub fn parse_cast(input: &[u8], input_fsp: i8, target_fsp: i8) -> Result<Duration> {
+        check_fsp(input_fsp)?;
+        Duration::parse(input, MAX_FSP)?.round_frac(target_fsp)
+    }
 
-                Some(Duration::new(
-                    false,
-                    hours,
-                    minutes,
-                    secs,
-                    micros,
-                    self.fsp().max(rhs.fsp()),
-                ))
+    /// Like `parse`, but first strips a digit-grouping separator (e.g. `,`
+    /// in `"1,2345"`) wherever it sits between two digits. A space
+    /// separator is rejected outright: the grammar already gives a space
+    /// between digits day-number meaning, and silently merging the two
+    /// would flip `"1 2345"` from `1 day 23:45` to `12345`.
+    pub fn parse_grouped(input: &[u8], fsp: i8, group_sep: u8) -> Result<Duration> {
+        if group_sep == b' ' {
+            return Err(invalid_type!(
+                "group separator ' ' conflicts with the day separator"
+            ));
+        }
+
+        let mut stripped = Vec::with_capacity(input.len());
+        for (i, &c) in input.iter().enumerate() {
+            if c == group_sep
+                && i > 0
+                && input[i - 1].is_ascii_digit()
+                && input.get(i + 1).map_or(false, u8::is_ascii_digit)
+            {
+                continue;
             }
+            stripped.push(c);
         }
+        Duration::parse(&stripped, fsp)
     }
 
-    /// Checked duration subtraction. Computes self - rhs, returning None if overflow occurred.
-    pub fn checked_sub(self, rhs: Duration) -> Option<Duration> {
-        match (self.get_neg(), rhs.get_neg()) {
-            (false, true) => self.checked_add(rhs.abs()),
-            (true, false) => self.abs().checked_add(rhs.abs()).map(|mut res| {
-                res.set_neg(true);
-                res
-            }),
-            (true, true) => rhs.abs().checked_sub(self.abs()),
-            (false, false) => {
-                let neg = self < rhs;
+    /// Parses returning both the value and the effective fsp used. With the
+    /// `UNSPECIFIED_FSP` sentinel the fsp is inferred from the number of
+    /// fraction digits the input spells out (capped at `MAX_FSP`), giving
+    /// "auto unless specified" semantics for schema inference; any other
+    /// `requested_fsp` behaves exactly like `parse`.
+    pub fn parse_detecting_fsp(input: &[u8], requested_fsp: i8) -> Result<(Duration, u8)> {
+        let fsp = if requested_fsp == UNSPECIFIED_FSP {
+            match input.iter().position(|&c| c == b'.') {
+                Some(pos) => input[pos + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .count()
+                    .min(MAX_FSP as usize) as i8,
+                None => 0,
+            }
+        } else {
+            requested_fsp
+        };
 
-                let (l, r) = if neg { (rhs, self) } else { (self, rhs) };
+        let duration = Duration::parse(input, fsp)?;
+        Ok((duration, duration.fsp()))
+    }
 
-                let mut micros = l.micros() as i32 - r.micros() as i32;
-                let mut secs = l.secs() as i32 - r.secs() as i32;
-                let mut minutes = l.minutes() as i32 - r.minutes() as i32;
-                let mut hours = l.hours() as i32 - r.hours() as i32;
+    /// Like `parse`, but rejects inputs whose fractional part spells out
+    /// more than `max_digits` digits instead of silently rounding them, for
+    /// columns that treat excess precision as an error. `max_digits` is
+    /// independent of `fsp`, which still controls the stored precision.
+    pub fn parse_max_frac_digits(input: &[u8], fsp: i8, max_digits: usize) -> Result<Duration> {
+        if let Some(pos) = input.iter().position(|&c| c == b'.') {
+            let digits = input[pos + 1..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .count();
+            if digits > max_digits {
+                return Err(invalid_type!(
+                    "fractional part has {} digits (max {})",
+                    digits,
+                    max_digits
+                ));
+            }
+        }
+        Duration::parse(input, fsp)
+    }
 
-                if micros < 0 {
-                    micros += MICROS_PER_SEC as i32;
-                    secs -= 1;
-                }
+    /// The tightest grammar for well-formed machine input:
+    /// `[-+]?\d+(:\d+){0,2}(\.\d+)?` with no whitespace anywhere, no
+    /// day-number space separator, rejected fast by a single shape scan
+    /// before the nom grammar runs. Values that pass parse exactly as
+    /// `parse` would parse them.
+    pub fn parse_digits_colon_only(input: &[u8], fsp: i8) -> Result<Duration> {
+        let mut rest = input;
+        if let Some((&sign, tail)) = rest.split_first() {
+            if sign == b'-' || sign == b'+' {
+                rest = tail;
+            }
+        }
 
-                if secs < 0 {
-                    secs += 60;
-                    minutes -= 1;
+        let mut colons = 0;
+        let mut seen_dot = false;
+        let mut digits = 0;
+        for &c in rest {
+            match c {
+                b'0'..=b'9' => digits += 1,
+                b':' if !seen_dot && colons < 2 && digits > 0 => {
+                    colons += 1;
+                    digits = 0;
                 }
-
-                if minutes < 0 {
-                    minutes += 60;
-                    hours -= 1;
+                b'.' if !seen_dot && digits > 0 => {
+                    seen_dot = true;
+                    digits = 0;
                 }
+                _ => return Err(invalid_type!("invalid time format")),
+            }
+        }
+        if digits == 0 {
+            return Err(invalid_type!("invalid time format"));
+        }
 
-                Some(Duration::new(
-                    neg,
-                    hours as u32,
-                    minutes as u32,
-                    secs as u32,
-                    micros as u32,
-                    self.fsp().max(rhs.fsp()),
-                ))
+        Duration::parse(input, fsp)
+    }
+
+    /// Like `parse`, but recognizes a trailing `-` (an export convention in
+    /// some systems) and applies it as the duration's sign:
+    /// `"12:00:00-"` parses as `-12:00:00`. This is not MySQL syntax and is
+    /// strictly opt-in; leading-sign input works here unchanged, but mixing
+    /// both signs is rejected.
+    pub fn parse_trailing_sign(input: &[u8], fsp: i8) -> Result<Duration> {
+        match input.split_last() {
+            Some((&b'-', rest)) => {
+                if rest.iter().any(|&c| c == b'-' || c == b'+') {
+                    return Err(invalid_type!("invalid time format"));
+                }
+                let mut duration = Duration::parse(rest, fsp)?;
+                if !duration.is_zero() {
+                    duration.set_neg(true);
+                }
+                Ok(duration)
             }
+            _ => Duration::parse(input, fsp),
         }
     }
 
-    fn format(self, sep: &str) -> String {
-        use std::fmt::Write;
-        let mut string = String::new();
-        if self.get_neg() {
-            string.push('-');
+    /// Like `parse`, but accepts a fourth colon-separated field as the
+    /// fractional seconds: `"12:34:56:789"` parses as `12:34:56.789`, with
+    /// the fraction read per its digit count exactly like `".789"` would be.
+    /// Note the contrast with `from_timecode`, where the fourth field is a
+    /// frame index scaled by the fps. Inputs without a fourth field go
+    /// through `parse` unchanged.
+    pub fn parse_colon_frac(input: &[u8], fsp: i8) -> Result<Duration> {
+        if input.iter().filter(|&&c| c == b':').count() == 3 {
+            let mut rewritten = input.to_vec();
+            let last = rewritten.iter().rposition(|&c| c == b':').unwrap();
+            rewritten[last] = b'.';
+            return Duration::parse(&rewritten, fsp);
         }
+        Duration::parse(input, fsp)
+    }
 
-        write!(
-            &mut string,
-            "{:02}{}{:02}{}{:02}",
-            self.hours(),
-            sep,
-            self.minutes(),
-            sep,
-            self.secs()
+    /// Like `parse`, but mandates the full `HH:MM:SS` form: `"12:34"` (which
+    /// `parse` accepts as `12:34:00`) is rejected, as are bare numbers and
+    /// the day-prefixed forms. For strict column definitions that require
+    /// all three fields to be spelled out.
+    pub fn parse_require_seconds(input: &[u8], fsp: i8) -> Result<Duration> {
+        let fsp = check_fsp(fsp)?;
+
+        let (_, [day, hour, minute, second, _]) = self::parser::parse(input)
+            .map_err(|_| invalid_type!("invalid time format"))?
+            .1;
+
+        if day.is_some() || hour.is_none() || minute.is_none() || second.is_none() {
+            return Err(invalid_type!("seconds field required"));
+        }
+
+        Duration::parse(input, fsp as i8)
+    }
+
+    /// Like `parse`, but first normalizes Unicode whitespace (NBSP and
+    /// friends) to ASCII spaces. The nom grammar only knows ASCII whitespace,
+    /// so copy-pasted values holding e.g. `\u{00A0}` would otherwise fail.
+    /// Input that is not valid UTF-8 is parsed as-is.
+    pub fn parse_unicode_ws(input: &[u8], fsp: i8) -> Result<Duration> {
+        match str::from_utf8(input) {
+            Ok(input) => {
+                let normalized: String = input
+                    .chars()
+                    .map(|c| if c.is_whitespace() { ' ' } else { c })
+                    .collect();
+                Duration::parse(normalized.as_bytes(), fsp)
+            }
+            Err(_) => Duration::parse(input, fsp),
+        }
+    }
+
+    /// Dumps the raw parser output and the interpretation `parse` gives it as
+    /// a human-readable string, for diagnosing the block/day disambiguation
+    /// (e.g. whether `"1 12"` treats `12` as hours or as an `HHMMSS` block).
+    /// Debugging aid only; the format is not stable.
+    pub fn debug_parse(input: &[u8], fsp: i8) -> String {
+        use std::fmt::Write;
+
+        let mut dump = format!("input: {:?}, fsp: {}\n", String::from_utf8_lossy(input), fsp);
+        let fsp = match check_fsp(fsp) {
+            Ok(fsp) => fsp,
+            Err(e) => {
+                write!(dump, "error: {}", e).unwrap();
+                return dump;
+            }
+        };
+
+        match self::parser::parse(input) {
+            Ok((_, (neg, [day, hour, minute, second, fraction]))) => {
+                writeln!(
+                    dump,
+                    "raw: neg: {}, day: {:?}, hour: {:?}, minute: {:?}, second: {:?}, \
+                     fraction: {:?}",
+                    neg, day, hour, minute, second, fraction
+                )
+                .unwrap();
+                let interpretation = match (day, hour) {
+                    (Some(block), None) => format!(
+                        "block {} reinterpreted as {:02}:{:02}:{:02}",
+                        block,
+                        block / 10_000,
+                        block / 100 % 100,
+                        block % 100
+                    ),
+                    (Some(day), Some(hour)) => {
+                        format!("day {} folded into hours: {}", day, day * 24 + hour)
+                    }
+                    _ => "plain hh:mm:ss".to_owned(),
+                };
+                writeln!(dump, "interpretation: {}", interpretation).unwrap();
+            }
+            Err(_) => {
+                dump.push_str("raw: parser failed\n");
+            }
+        }
+
+        match Duration::parse(input, fsp as i8) {
+            Ok(duration) => write!(dump, "parsed: {}", duration).unwrap(),
+            Err(e) => write!(dump, "error: {}", e).unwrap(),
+        }
+        dump
+    }
+
+    /// Parses an SMPTE timecode `HH:MM:SS:FF` where the last field is a frame
+    /// index, converting frames to fractional seconds via `fps`. The frame
+    /// field must be smaller than `fps`.
+    pub fn from_timecode(input: &[u8], fps: u32, fsp: i8) -> Result<Duration> {
+        let fsp = check_fsp(fsp)?;
+        if fps == 0 {
+            return Err(invalid_type!("invalid fps value: {}", fps));
+        }
+
+        let fields: Vec<&[u8]> = input.split(|&c| c == b':').collect();
+        if fields.len() != 4
+            || fields
+                .iter()
+                .any(|f| f.is_empty() || f.len() > 7 || f.iter().any(|c| !c.is_ascii_digit()))
+        {
+            return Err(invalid_type!("invalid timecode format"));
+        }
+
+        let read = |buf: &[u8]| {
+            buf.iter()
+                .fold(0, |acc, c| acc * 10 + u32::from(c - b'0'))
+        };
+
+        let mut hours = check_hour(read(fields[0]))?;
+        let mut minutes = check_minute(read(fields[1]))?;
+        let mut secs = check_second(read(fields[2]))?;
+        let frames = read(fields[3]);
+        if frames >= fps {
+            return Err(invalid_type!(
+                "frame value {} out of range at {} fps",
+                frames,
+                fps
+            ));
+        }
+
+        // Aligned to NANO_WIDTH digits, as `round` expects.
+        let mut nanos = ((u64::from(frames) * NANOS_PER_SEC as u64 + u64::from(fps) / 2)
+            / u64::from(fps)) as u32;
+        round(&mut hours, &mut minutes, &mut secs, &mut nanos, fsp)?;
+        Ok(Duration::new(false, hours, minutes, secs, nanos, fsp))
+    }
+
+    /// Renders the duration as an SMPTE timecode `HH:MM:SS:FF`, converting
+    /// the sub-second part to a frame index via `fps` (rounded to the nearest
+    /// frame). Days fold into the hour field; a negative duration is prefixed
+    /// with `-`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fps` is 0.
+    pub fn to_timecode(self, fps: u32) -> String {
+        assert!(fps > 0, "invalid fps value: 0");
+        let total_micros = u64::from(self.hours()) * u64::from(SECS_PER_HOUR) * MICROS_PER_SEC
+            as u64
+            + u64::from(self.minutes()) * u64::from(SECS_PER_MINUTE) * MICROS_PER_SEC as u64
+            + u64::from(self.secs()) * MICROS_PER_SEC as u64
+            + u64::from(self.micros());
+        let total_frames = (total_micros * u64::from(fps) + MICROS_PER_SEC as u64 / 2)
+            / MICROS_PER_SEC as u64;
+
+        format!(
+            "{}{:02}:{:02}:{:02}:{:02}",
+            if self.get_neg() { "-" } else { "" },
+            total_frames / u64::from(fps) / 3600,
+            total_frames / u64::from(fps) / 60 % 60,
+            total_frames / u64::from(fps) % 60,
+            total_frames % u64::from(fps),
         )
-        .unwrap();
+    }
 
-        let fsp = usize::from(self.fsp());
+    /// Parses like `parse` after stripping a leading UTF-8 BOM (`EF BB BF`),
+    /// which real-world CSV exports sometimes prefix to the first cell.
+    /// `parse` itself stays byte-exact.
+    pub fn parse_skip_bom(input: &[u8], fsp: i8) -> Result<Duration> {
+        let input = if input.starts_with(b"\xef\xbb\xbf") {
+            &input[3..]
+        } else {
+            input
+        };
+        Duration::parse(input, fsp)
+    }
 
-        if self.fsp() > 0 {
-            write!(
-                &mut string,
-                ".{:0width$}",
-                self.micros() / TEN_POW[MICRO_WIDTH - fsp],
-                width = fsp
-            )
-            .unwrap();
+    /// Parses a fixed-width, digits-only `HHHMMSS` literal. The value is
+    /// right aligned, so `b"0123045"` and `b"123045"` both mean `12:30:45`,
+    /// consistent with the block interpretation of `parse` (`b"12345"` is
+    /// still `01:23:45`), but anything except plain digits is rejected.
+    pub fn parse_fixed_hhmmss(input: &[u8], fsp: i8) -> Result<Duration> {
+        if input.is_empty() || input.len() > 7 || input.iter().any(|c| !c.is_ascii_digit()) {
+            return Err(invalid_type!("invalid fixed-width time value"));
         }
 
-        string
+        let value = input
+            .iter()
+            .fold(0, |acc, c| acc * 10 + i64::from(c - b'0'));
+        Duration::parse_numeric(value, fsp)
     }
-}
 
-impl TryFrom<Duration> for Decimal {
-    type Error = crate::coprocessor::codec::Error;
-    fn try_from(duration: Duration) -> Result<Decimal> {
-        duration.format("").parse()
+    /// Constructs a `Duration` from a numeric literal, interpreting the value
+    /// as `[H]HHMMSS` the way MySQL casts bare numbers to TIME, e.g.
+    /// `123456` means `12:34:56`.
+    pub fn parse_numeric(value: i64, fsp: i8) -> Result<Duration> {
+        let fsp = check_fsp(fsp)?;
+        let neg = value < 0;
+        let value = value
+            .checked_abs()
+            .ok_or_else(|| invalid_type!("invalid time value: {}", value))?;
+
+        let hours = value / 10_000;
+        if hours > i64::from(MAX_HOURS) {
+            return Err(invalid_type!(
+                "invalid hour value: {} larger than {}",
+                hours,
+                MAX_HOURS
+            ));
+        }
+
+        let hours = hours as u32;
+        let minutes = check_minute((value / 100 % 100) as u32)?;
+        let secs = check_second((value % 100) as u32)?;
+
+        if hours == 0 && minutes == 0 && secs == 0 {
+            return Ok(Duration::new(false, 0, 0, 0, 0, fsp));
+        }
+
+        Ok(Duration::new(neg, hours, minutes, secs, 0, fsp))
     }
-}
 
-impl Display for Duration {
-    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
-        write!(formatter, "{}", self.format(":"))
+    /// The int-literal path for `CAST(123456 AS TIME)`: interprets the value
+    /// as `[H]HHMMSS` via `parse_numeric`, without rendering it to a string
+    /// first. Values past `±8385959` (that is, `838:59:59`) are an overflow
+    /// error for the caller to handle; a value in range with an invalid
+    /// minute or second part (e.g. `126099`) becomes a zero duration with a
+    /// truncation warning under `Flag::TRUNCATE_AS_WARNING`, the non-strict
+    /// MySQL behavior, and errors otherwise.
+    pub fn from_i64(
+        ctx: &mut crate::coprocessor::dag::expr::EvalContext,
+        value: i64,
+        fsp: i8,
+    ) -> Result<Duration> {
+        let fsp = check_fsp(fsp)?;
+        if value > i64::from(MAX_HOURS) * 10_000 + 5_959
+            || value < -(i64::from(MAX_HOURS) * 10_000 + 5_959)
+        {
+            return Err(crate::coprocessor::codec::Error::overflow(
+                "TIME",
+                &format!("{}", value),
+            ));
+        }
+        match Duration::parse_numeric(value, fsp as i8) {
+            Ok(duration) => Ok(duration),
+            Err(err) => {
+                ctx.handle_truncate_err(err)?;
+                Ok(Duration::new(false, 0, 0, 0, 0, fsp))
+            }
+        }
     }
-}
 
-impl PartialEq for Duration {
-    fn eq(&self, dur: &Duration) -> bool {
-        let (mut a, mut b) = (*self, *dur);
-        a.set_fsp(0);
-        b.set_fsp(0);
-        a.0 == b.0
+    /// The `Decimal`-literal path for `CAST(113045.5 AS TIME)`: splits the
+    /// integer part as `[H]HHMMSS` numerically and reads the fractional
+    /// seconds off the decimal's digits, so no precision is lost on either
+    /// side. Ranges are validated the same way `parse_numeric` validates
+    /// them.
+    pub fn from_packed_decimal(dec: &Decimal, fsp: i8) -> Result<Duration> {
+        let checked_fsp = check_fsp(fsp)?;
+
+        let int = match dec.as_i64() {
+            Res::Ok(v) | Res::Truncated(v) => v,
+            Res::Overflow(_) => return Err(invalid_type!("invalid time value: {}", dec)),
+        };
+
+        let base = Duration::parse_numeric(int, fsp)?;
+        let (mut hours, mut minutes, mut secs) = (base.hours(), base.minutes(), base.secs());
+
+        // fractional seconds, aligned to NANO_WIDTH digits the way `round`
+        // expects. The fraction is taken from the decimal's own digits: going
+        // through f64 loses an ulp near seven-digit `[H]HHMMSS` integer
+        // parts, which is enough to flip half-up rounding at the fsp
+        // boundary.
+        let mut nanos = 0;
+        let rendered = dec.to_string();
+        if let Some(dot) = rendered.find('.') {
+            let frac_digits = &rendered.as_bytes()[dot + 1..];
+            let len = frac_digits.len().min(NANO_WIDTH);
+            nanos = frac_digits[..len]
+                .iter()
+                .fold(0, |acc, d| acc * 10 + u32::from(d - b'0'))
+                * TEN_POW[NANO_WIDTH - len];
+        }
+
+        round(&mut hours, &mut minutes, &mut secs, &mut nanos, checked_fsp)?;
+
+        let neg = *dec < Decimal::zero()
+            && !(hours == 0 && minutes == 0 && secs == 0 && nanos == 0);
+        Ok(Duration::new(neg, hours, minutes, secs, nanos, checked_fsp))
     }
-}
 
-impl std::hash::Hash for Duration {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        let mut inner = *self;
-        inner.set_fsp(0);
-        inner.to_bits().hash(state)
+    /// The `Decimal` companion of `parse_numeric`: the integer part is
+    /// interpreted as `[H]HHMMSS` and the fractional part as fractional
+    /// seconds, rounded with `fsp`.
+    pub fn parse_numeric_decimal(dec: &Decimal, fsp: i8) -> Result<Duration> {
+        Duration::parse(dec.to_string().as_bytes(), fsp)
     }
-}
 
-impl PartialOrd for Duration {
-    fn partial_cmp(&self, dur: &Duration) -> Option<Ordering> {
-        let mut a = *self;
-        let mut b = *dur;
+    /// A strict variant of `parse` that reports a dedicated error when the
+    /// day field and an explicit hour field are individually in range but
+    /// overflow the TIME range once combined, e.g. `"34 23:00:00"`.
+    pub fn parse_no_day_hour_overflow(input: &[u8], fsp: i8) -> Result<Duration> {
+        if input.is_empty() {
+            return Err(invalid_type!("invalid time format"));
+        }
 
-        a.set_fsp(0);
-        b.set_fsp(0);
+        check_fsp(fsp)?;
+        let (_, [day, hour, _, _, _]) = self::parser::parse(input)
+            .map_err(|_| invalid_type!("invalid time format"))?
+            .1;
 
-        let ordering = a.0.cmp(&b.0);
-        Some(if a.get_neg() || b.get_neg() {
-            ordering.reverse()
-        } else {
-            ordering
+        if let (Some(day), Some(hour)) = (day, hour) {
+            if day * 24 <= MAX_HOURS && day * 24 + hour > MAX_HOURS {
+                return Err(invalid_type!(
+                    "day {} and hour {} combined out of range",
+                    day,
+                    hour
+                ));
+            }
+        }
+
+        Duration::parse(input, fsp)
+    }
+
+    /// Parses like `parse`, additionally recording a truncation warning on
+    /// the context and reporting whether rounding to `fsp` modified the
+    /// value, so callers can both surface warnings and act on the precision
+    /// loss.
+    pub fn parse_ctx_reporting(
+        ctx: &mut crate::coprocessor::dag::expr::EvalContext,
+        input: &[u8],
+        fsp: i8,
+    ) -> Result<(Duration, bool)> {
+        check_fsp(fsp)?;
+        let full = Duration::parse(input, MAX_FSP)?;
+        let rounded = full.round_frac(fsp)?;
+        let modified = full.to_nanos() != rounded.to_nanos();
+        if modified {
+            ctx.warnings
+                .append_warning(crate::coprocessor::codec::Error::truncated_wrong_val(
+                    "TIME",
+                    &format!("{}", full),
+                ));
+        }
+        Ok((rounded, modified))
+    }
+
+    /// `parse` that honors the statement context's truncate-vs-round choice.
+    /// By default (and always in strict mode) the fractional part is rounded
+    /// half-up exactly like `parse`; when the context carries
+    /// `Flag::TRUNCATE_AS_WARNING` the digits past `fsp` are discarded
+    /// instead, the non-strict MySQL insert behavior, and a truncation
+    /// warning is recorded whenever that discards non-zero digits.
+    pub fn parse_with_ctx(
+        ctx: &mut crate::coprocessor::dag::expr::EvalContext,
+        input: &[u8],
+        fsp: i8,
+    ) -> Result<Duration> {
+        use crate::coprocessor::dag::expr::Flag;
+
+        let fsp = check_fsp(fsp)?;
+        if !ctx.cfg.flag.contains(Flag::TRUNCATE_AS_WARNING) {
+            return Duration::parse(input, fsp as i8);
+        }
+
+        if input.is_empty() {
+            return Err(invalid_type!("invalid time format"));
+        }
+
+        let (mut neg, [mut day, mut hour, mut minute, mut second, nanos]) =
+            self::parser::parse(input)
+                .map_err(|_| invalid_type!("invalid time format"))?
+                .1;
+
+        if day.is_some() && hour.is_none() {
+            let block = day.take().unwrap();
+            hour = Some(block / 10_000);
+            minute = Some(block / 100 % 100);
+            second = Some(block % 100);
+        }
+
+        if let Some(day) = day {
+            if day * 24 + hour.unwrap_or(0) > MAX_HOURS {
+                return Err(invalid_type!("day value {} out of range", day));
+            }
+        }
+
+        let (hour, minute, second, nanos) = (
+            hour.unwrap_or(0) + day.unwrap_or(0) * 24,
+            minute.unwrap_or(0),
+            second.unwrap_or(0),
+            nanos.unwrap_or(0),
+        );
+        check_hour(hour)?;
+
+        let granularity = TEN_POW[NANO_WIDTH - usize::from(fsp)];
+        let truncated = nanos / granularity * granularity;
+        if truncated != nanos {
+            ctx.warnings
+                .append_warning(crate::coprocessor::codec::Error::truncated_wrong_val(
+                    "TIME",
+                    &String::from_utf8_lossy(input),
+                ));
+        }
+
+        if hour == 0 && minute == 0 && second == 0 && truncated == 0 {
+            neg = false;
+        }
+        Ok(Duration::new(neg, hour, minute, second, truncated, fsp))
+    }
+
+    /// Non-strict parse matching MySQL's handling of out-of-range TIME
+    /// values: `"850:00:00"` clamps to the signed `±838:59:59` bound (plus
+    /// the widest fraction at `fsp`) and pushes a warning onto the context,
+    /// while malformed input still errors. The value-level behavior is
+    /// `OverflowPolicy::Saturate`; this wires it up to `EvalContext`.
+    pub fn parse_saturating(
+        ctx: &mut crate::coprocessor::dag::expr::EvalContext,
+        input: &[u8],
+        fsp: i8,
+    ) -> Result<Duration> {
+        if let Ok(duration) = Duration::parse(input, fsp) {
+            return Ok(duration);
+        }
+
+        let duration = Duration::parse_with_policy(input, fsp, OverflowPolicy::Saturate)?;
+        ctx.warnings
+            .append_warning(crate::coprocessor::codec::Error::truncated_wrong_val(
+                "TIME",
+                &String::from_utf8_lossy(input),
+            ));
+        Ok(duration)
+    }
+
+    /// Parses like `parse`, but input that only fails because of a trailing
+    /// garbage suffix (`"11:22:33xyz"`) or a cut-off tail (`"11:22:"`) is
+    /// salvaged from the longest prefix that still parses, with a
+    /// "Truncated incorrect time value" warning recorded on the context,
+    /// matching MySQL. In strict mode (no `Flag::TRUNCATE_AS_WARNING`) the
+    /// truncation is an error instead; input with no parseable prefix at
+    /// all keeps the original parse error.
+    pub fn parse_best_effort(
+        ctx: &mut crate::coprocessor::dag::expr::EvalContext,
+        input: &[u8],
+        fsp: i8,
+    ) -> Result<Duration> {
+        match Duration::parse(input, fsp) {
+            Ok(duration) => Ok(duration),
+            Err(err) => {
+                for end in (1..input.len()).rev() {
+                    if let Ok(duration) = Duration::parse(&input[..end], fsp) {
+                        ctx.handle_truncate_err(
+                            crate::coprocessor::codec::Error::truncated_wrong_val(
+                                "TIME",
+                                &String::from_utf8_lossy(input),
+                            ),
+                        )?;
+                        return Ok(duration);
+                    }
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Rounds fractional seconds precision with new FSP and returns a new one.
+    /// We will use the “round half up” rule, e.g, >= 0.5 -> 1, < 0.5 -> 0,
+    /// so 10:10:10.999999 round with fsp: 1 -> 10:10:11.0
+    /// and 10:10:10.000000 round with fsp: 0 -> 10:10:11
+    pub fn round_frac(mut self, fsp: i8) -> Result<Self> {
+        let fsp = check_fsp(fsp)?;
+
+        if fsp >= self.fsp() {
+            self.set_fsp(fsp);
+            return Ok(self);
+        }
+
+        let mut hours = self.hours();
+        let mut minutes = self.minutes();
+        let mut secs = self.secs();
+        let mut nanos = self.subsec_nanos();
+
+        round(&mut hours, &mut minutes, &mut secs, &mut nanos, fsp)?;
+
+        Ok(Duration::new(
+            self.get_neg(),
+            hours,
+            minutes,
+            secs,
+            nanos,
+            fsp,
+        ))
+    }
+
+    /// Re-tags the precision without touching the value: errors if the
+    /// stored micros carry significant digits beyond `fsp` (nothing is ever
+    /// rounded away silently), otherwise only the fsp field changes. The
+    /// strict counterpart to `round_frac` for callers who guarantee the
+    /// retag is lossless.
+    pub fn retag_fsp(mut self, fsp: i8) -> Result<Duration> {
+        let fsp = check_fsp(fsp)?;
+        if !self.fits_fsp(fsp) {
+            return Err(invalid_type!(
+                "retagging {} to fsp {} would lose precision",
+                self,
+                fsp
+            ));
+        }
+        self.set_fsp(fsp);
+        Ok(self)
+    }
+
+    /// Infallible `round_frac` for non-strict casts: an out-of-range `fsp`
+    /// is clamped into `[MIN_FSP, MAX_FSP]`, and a rounding carry past
+    /// `838:59:59` saturates to the range bound instead of erroring.
+    pub fn saturating_round_frac(self, fsp: i8) -> Duration {
+        let fsp = fsp.max(MIN_FSP).min(MAX_FSP);
+        self.round_frac(fsp)
+            .unwrap_or_else(|_| Duration::saturate(self.get_neg(), fsp as u8))
+    }
+
+    /// Combines the signed duration with a base date, returning the resulting
+    /// wall-clock `Time`. Day rollover is carried into the date part; leaving
+    /// the supported date range is an error.
+    pub fn combine_with_date(self, date: Time) -> Result<Time> {
+        date.clone().checked_add(self).ok_or_else(|| {
+            invalid_type!("datetime overflow when combining {} and {}", date, self)
         })
     }
-}
 
-impl Eq for Duration {}
+    /// Adds the signed duration to a full datetime, propagating day carries
+    /// into the date part. This backs `ADDTIME(datetime, time)`; leaving the
+    /// supported date range is an error.
+    pub fn add_to_time(self, t: Time) -> Result<Time> {
+        t.clone()
+            .checked_add(self)
+            .ok_or_else(|| invalid_type!("datetime overflow in ADDTIME({}, {})", t, self))
+    }
 
-impl Ord for Duration {
-    fn cmp(&self, dur: &Duration) -> Ordering {
-        self.partial_cmp(dur).unwrap()
+    /// Promotes the duration to a full `DATETIME` anchored on the current
+    /// date in the session time zone, matching what MySQL does when
+    /// `ADDTIME`/`SUBTIME` mix a TIME argument with a DATETIME one.
+    pub fn to_datetime(
+        self,
+        ctx: &mut crate::coprocessor::dag::expr::EvalContext,
+    ) -> Result<Time> {
+        Time::from_duration(&ctx.cfg.tz, TimeType::DateTime, self)
     }
-}
 
-impl<T: Write> DurationEncoder for T {}
-pub trait DurationEncoder: NumberEncoder {
-    fn encode_duration(&mut self, v: Duration) -> Result<()> {
-        self.encode_i64(v.to_nanos())?;
-        self.encode_i64(i64::from(v.get_fsp())).map_err(From::from)
+    /// Subtracts the signed duration from a full datetime, borrowing from the
+    /// date part when crossing a day boundary. This backs
+    /// `SUBTIME(datetime, time)`; leaving the supported date range is an
+    /// error.
+    pub fn sub_from_time(self, t: Time) -> Result<Time> {
+        t.clone()
+            .checked_sub(self)
+            .ok_or_else(|| invalid_type!("datetime overflow in SUBTIME({}, {})", t, self))
     }
-}
 
-impl Duration {
-    /// `decode` decodes duration encoded by `encode_duration`.
-    pub fn decode(data: &mut BytesSlice<'_>) -> Result<Duration> {
-        let nanos = number::decode_i64(data)?;
-        let fsp = number::decode_i64(data)?;
-        Duration::from_nanos(nanos, fsp as i8)
+    /// Applies `round_frac` to every element of a slice in place, reporting
+    /// the index of the first element that overflows.
+    pub fn round_frac_slice(durations: &mut [Duration], fsp: i8) -> Result<()> {
+        check_fsp(fsp)?;
+        for (index, duration) in durations.iter_mut().enumerate() {
+            *duration = duration
+                .round_frac(fsp)
+                .map_err(|e| invalid_type!("round_frac failed at index {}: {}", index, e))?;
+        }
+        Ok(())
     }
-}
 
-impl crate::coprocessor::codec::data_type::AsMySQLBool for Duration {
-    #[inline]
-    fn as_mysql_bool(
-        &self,
-        _context: &mut crate::coprocessor::dag::expr::EvalContext,
-    ) -> crate::coprocessor::Result<bool> {
-        Ok(!self.is_zero())
+    /// Checked duration addition. Computes self + rhs, returning None if overflow occurred.
+    pub fn checked_add(self, rhs: Duration) -> Option<Duration> {
+        match (self.get_neg(), rhs.get_neg()) {
+            (false, true) => self.checked_sub(rhs.abs()),
+            (true, false) => rhs.checked_sub(self.abs()),
+            (true, true) => self.abs().checked_add(rhs.abs()).map(|mut res| {
+                res.set_neg(true);
+                res
+            }),
+            (false, false) => {
+                let mut nanos = self.subsec_nanos() + rhs.subsec_nanos();
+                let mut secs = self.secs() + rhs.secs();
+                let mut minutes = self.minutes() + rhs.minutes();
+                let mut hours = self.hours() + rhs.hours();
+
+                if i64::from(nanos) >= NANOS_PER_SEC {
+                    nanos -= NANOS_PER_SEC as u32;
+                    secs += 1;
+                }
+                if secs >= 60 {
+                    secs -= 60;
+                    minutes += 1;
+                }
+                if minutes >= 60 {
+                    minutes -= 60;
+                    hours += 1;
+                }
+
+                check_hour(hours).ok()?;
+
+                Some(Duration::new(
+                    false,
+                    hours,
+                    minutes,
+                    secs,
+                    nanos,
+                    self.fsp().max(rhs.fsp()),
+                ))
+            }
+        }
+    }
+
+    /// Adds two durations and rounds the result to `fsp` in one step, the
+    /// combined primitive for `col_a + col_b` where the target column has a
+    /// fixed fsp. Overflow of either step is an error.
+    pub fn add_clamp_fsp(self, rhs: Duration, fsp: i8) -> Result<Duration> {
+        self.checked_add(rhs)
+            .ok_or_else(|| invalid_type!("duration overflow when adding {} and {}", self, rhs))?
+            .round_frac(fsp)
+    }
+
+    /// Adds `delta` on a 24h clock, returning the wrapped time-of-day and the
+    /// signed number of days the addition crossed. The result is always in
+    /// `[00:00:00, 24:00:00)` with the wider fsp of the two operands, so
+    /// unlike `checked_add` this can never overflow the `Duration` range.
+    pub fn add_clock_reporting(self, delta: Duration) -> (Duration, i64) {
+        const DAY_MICROS: i64 = 24 * 3600 * MICROS_PER_SEC;
+
+        let sum = self.to_nanos() / 1000 + delta.to_nanos() / 1000;
+        // `div_euclid` spelled out: wrap the remainder into [0, DAY_MICROS)
+        let mut days = sum / DAY_MICROS;
+        let mut rem = sum % DAY_MICROS;
+        if rem < 0 {
+            rem += DAY_MICROS;
+            days -= 1;
+        }
+
+        let fsp = self.fsp().max(delta.fsp());
+        (Duration::from_micros(rem, fsp as i8).unwrap(), days)
+    }
+
+    /// Sums a slice of durations and rounds the total to `fsp` once at the
+    /// end, the way `SUM` accumulates into a typed column. The intermediate
+    /// total keeps full microsecond precision, so no precision is lost to
+    /// repeated per-element rounding. Overflow of the running total is an
+    /// error.
+    pub fn sum_to_fsp(values: &[Duration], fsp: i8) -> Result<Duration> {
+        let mut total = Duration::zero();
+        for value in values {
+            total = total
+                .checked_add(*value)
+                .ok_or_else(|| invalid_type!("duration overflow when summing {}", value))?;
+        }
+        total.round_frac(fsp)
+    }
+
+    /// Checked duration scaling. Computes self * rhs on the total
+    /// microseconds with MySQL's half-up rounding, so a sub-second product
+    /// carries into the seconds part through the usual normalization
+    /// (`0.500000 * 3` is `1.500000`). The result keeps `self`'s fsp;
+    /// a non-finite factor or a product outside the `Duration` range
+    /// returns None.
+    pub fn checked_mul(self, rhs: f64) -> Option<Duration> {
+        let product = (self.to_nanos() / 1000) as f64 * rhs;
+        Duration::from_micros_f64(product, self.fsp() as i8)
+    }
+
+    /// Checked duration division, the TIME / number counterpart of
+    /// `checked_mul`. Division by zero yields a non-finite quotient and so
+    /// returns None, as does a quotient outside the `Duration` range.
+    pub fn checked_div(self, rhs: f64) -> Option<Duration> {
+        let quotient = (self.to_nanos() / 1000) as f64 / rhs;
+        Duration::from_micros_f64(quotient, self.fsp() as i8)
+    }
+
+    /// Shared tail of the fractional scaling ops: rounds the microsecond
+    /// total half away from zero and rejects values that don't fit.
+    fn from_micros_f64(micros: f64, fsp: i8) -> Option<Duration> {
+        if !micros.is_finite() {
+            return None;
+        }
+        let micros = micros.round();
+        if micros < i64::min_value() as f64 || micros > i64::max_value() as f64 {
+            return None;
+        }
+        Duration::from_micros(micros as i64, fsp).ok()
+    }
+
+    /// Saturating version of `checked_mul`: an overflowing product clamps to
+    /// the signed range bound (`±838:59:59` plus the widest fraction at the
+    /// fsp) instead of returning `None`, for infallible scaling paths. The
+    /// sign of the saturation follows the sign the product would have.
+    pub fn saturating_mul(self, rhs: i64) -> Duration {
+        match self.checked_mul(rhs as f64) {
+            Some(res) => res,
+            None => Duration::saturate(self.get_neg() != (rhs < 0), self.fsp()),
+        }
+    }
+
+    /// Null-aware `checked_add`: `None` on the right-hand side is the
+    /// identity, so folding a nullable column needs no per-element match.
+    /// `None` is still returned when the addition itself overflows.
+    pub fn checked_add_opt(self, rhs: Option<Duration>) -> Option<Duration> {
+        match rhs {
+            Some(rhs) => self.checked_add(rhs),
+            None => Some(self),
+        }
+    }
+
+    /// Like `checked_add`, but instead of `None` an overflowing addition
+    /// returns the range-saturated value (`±838:59:59.999999` truncated to
+    /// the result fsp) along with a flag reporting that overflow happened.
+    /// Addition only overflows when both operands share a sign, so the
+    /// saturation takes the sign of `self`.
+    pub fn overflowing_add(self, rhs: Duration) -> (Duration, bool) {
+        let fsp = self.fsp().max(rhs.fsp());
+        match self.checked_add(rhs) {
+            Some(res) => (res, false),
+            None => (Duration::saturate(self.get_neg(), fsp), true),
+        }
+    }
+
+    /// One-call consolidation of the overflow-reporting add variants:
+    /// returns the (possibly saturated) sum along with the overflow flag,
+    /// whether the result fsp is wider than `self`'s, and the signed whole
+    /// days the result spills past 24 hours. Callers needing only one of
+    /// these can keep using `overflowing_add`/`add_clock_reporting`.
+    pub fn add_full_report(self, rhs: Duration) -> AddReport {
+        let (value, overflowed) = self.overflowing_add(rhs);
+        AddReport {
+            value,
+            overflowed,
+            fsp_widened: value.fsp() > self.fsp(),
+            days: {
+                let days = i64::from(value.hours() / 24);
+                if value.get_neg() {
+                    -days
+                } else {
+                    days
+                }
+            },
+        }
+    }
+
+    /// `overflowing_add` for subtraction: subtraction only overflows when
+    /// the operand signs differ, so the saturation again takes the sign of
+    /// `self`.
+    pub fn overflowing_sub(self, rhs: Duration) -> (Duration, bool) {
+        let fsp = self.fsp().max(rhs.fsp());
+        match self.checked_sub(rhs) {
+            Some(res) => (res, false),
+            None => (Duration::saturate(self.get_neg(), fsp), true),
+        }
+    }
+
+    /// `checked_add` for non-strict statement contexts: an overflowing sum
+    /// saturates to the range bound (with the sign of `self`, like
+    /// `overflowing_add`) and pushes a warning onto the context instead of
+    /// failing the expression.
+    pub fn add_saturating(
+        self,
+        ctx: &mut crate::coprocessor::dag::expr::EvalContext,
+        rhs: Duration,
+    ) -> Duration {
+        let (res, overflowed) = self.overflowing_add(rhs);
+        if overflowed {
+            ctx.warnings
+                .append_warning(crate::coprocessor::codec::Error::truncated_wrong_val(
+                    "TIME",
+                    &format!("{} + {}", self, rhs),
+                ));
+        }
+        res
+    }
+
+    /// The subtraction counterpart of `add_saturating`.
+    pub fn sub_saturating(
+        self,
+        ctx: &mut crate::coprocessor::dag::expr::EvalContext,
+        rhs: Duration,
+    ) -> Duration {
+        let (res, overflowed) = self.overflowing_sub(rhs);
+        if overflowed {
+            ctx.warnings
+                .append_warning(crate::coprocessor::codec::Error::truncated_wrong_val(
+                    "TIME",
+                    &format!("{} - {}", self, rhs),
+                ));
+        }
+        res
+    }
+
+    /// Strict-mode counterpart to `sum_to_fsp`: overflow of the running
+    /// total is an error, and so is any element (and therefore any total)
+    /// carrying more precision than `fsp` can hold — nothing is rounded.
+    pub fn sum_strict_fsp(values: &[Duration], fsp: i8) -> Result<Duration> {
+        let granularity = TEN_POW[NANO_WIDTH - usize::from(check_fsp(fsp)?)];
+
+        let mut total = Duration::zero();
+        for value in values {
+            if value.subsec_nanos() % granularity != 0 {
+                return Err(invalid_type!(
+                    "{} requires more precision than fsp {}",
+                    value,
+                    fsp
+                ));
+            }
+            total = total
+                .checked_add(*value)
+                .ok_or_else(|| invalid_type!("duration overflow when summing {}", value))?;
+        }
+        total.retag_fsp(fsp)
+    }
+
+    /// Checked duration subtraction. Computes self - rhs, returning None if overflow occurred.
+    pub fn checked_sub(self, rhs: Duration) -> Option<Duration> {
+        match (self.get_neg(), rhs.get_neg()) {
+            (false, true) => self.checked_add(rhs.abs()),
+            (true, false) => self.abs().checked_add(rhs.abs()).map(|mut res| {
+                res.set_neg(true);
+                res
+            }),
+            (true, true) => rhs.abs().checked_sub(self.abs()),
+            (false, false) => {
+                let neg = self < rhs;
+
+                let (l, r) = if neg { (rhs, self) } else { (self, rhs) };
+
+                let mut nanos = l.subsec_nanos() as i32 - r.subsec_nanos() as i32;
+                let mut secs = l.secs() as i32 - r.secs() as i32;
+                let mut minutes = l.minutes() as i32 - r.minutes() as i32;
+                let mut hours = l.hours() as i32 - r.hours() as i32;
+
+                if nanos < 0 {
+                    nanos += NANOS_PER_SEC as i32;
+                    secs -= 1;
+                }
+
+                if secs < 0 {
+                    secs += 60;
+                    minutes -= 1;
+                }
+
+                if minutes < 0 {
+                    minutes += 60;
+                    hours -= 1;
+                }
+
+                Some(Duration::new(
+                    neg,
+                    hours as u32,
+                    minutes as u32,
+                    secs as u32,
+                    nanos as u32,
+                    self.fsp().max(rhs.fsp()),
+                ))
+            }
+        }
+    }
+
+    /// Constrains the value to `[min, max]` using `Ord`, returning the
+    /// nearest bound when out of range. Debug builds panic when
+    /// `min > max`.
+    pub fn clamp(self, min: Duration, max: Duration) -> Duration {
+        debug_assert!(min <= max, "clamp with min {} > max {}", min, max);
+        if self < min {
+            min
+        } else if self > max {
+            max
+        } else {
+            self
+        }
+    }
+
+    /// `checked_sub` additionally reporting whether the result's sign
+    /// flipped relative to `self`, i.e. the subtraction crossed zero —
+    /// `00:00:05 - 00:00:10` flips. A zero result never counts as flipped.
+    pub fn sub_reporting(self, rhs: Duration) -> Option<(Duration, bool)> {
+        let res = self.checked_sub(rhs)?;
+        let flipped = !res.is_zero() && res.get_neg() != self.get_neg();
+        Some((res, flipped))
+    }
+
+    /// Generates a uniformly random in-range `Duration` with a random fsp
+    /// up to `max_fsp` (clamped into range), for seeding property tests and
+    /// benchmarks. Generated values are always canonical.
+    #[cfg(test)]
+    pub fn random_valid<R: rand::Rng>(rng: &mut R, max_fsp: i8) -> Duration {
+        let max_fsp = max_fsp.max(MIN_FSP).min(MAX_FSP);
+        let fsp = rng.gen_range(0, i32::from(max_fsp) + 1) as u8;
+
+        let neg = rng.gen::<bool>();
+        let hours = rng.gen_range(0, MAX_HOURS + 1);
+        let minutes = rng.gen_range(0, MAX_MINUTES + 1);
+        let secs = rng.gen_range(0, MAX_SECONDS + 1);
+
+        let granularity = TEN_POW[NANO_WIDTH - usize::from(fsp)];
+        let nanos = rng.gen_range(0, TEN_POW[usize::from(fsp)]) * granularity;
+
+        let neg = neg && !(hours == 0 && minutes == 0 && secs == 0 && nanos == 0);
+        Duration::new(neg, hours, minutes, secs, nanos, fsp)
+    }
+
+    /// Returns the largest representable `Duration` (`838:59:59` plus the
+    /// widest fraction expressible at `fsp`) with the given sign.
+    pub fn saturate(neg: bool, fsp: u8) -> Duration {
+        let granularity = TEN_POW[NANO_WIDTH - usize::from(fsp)];
+        Duration::new(
+            neg,
+            MAX_HOURS,
+            MAX_MINUTES,
+            MAX_SECONDS,
+            MAX_NANOS / granularity * granularity,
+            fsp,
+        )
+    }
+
+    /// Canonical value equality: two `Duration`s are equal iff their signed
+    /// magnitude matches, regardless of fsp. `12:00:00` at fsp 0 equals
+    /// `12:00:00.000000` at fsp 6, while `12:00:00.5` never equals
+    /// `12:00:00`. This is the semantics `Eq` already provides (fsp is
+    /// zeroed before comparing); this method pins them down explicitly.
+    pub fn value_eq(self, other: Duration) -> bool {
+        self.to_nanos() == other.to_nanos()
+    }
+
+    /// Compares two `Duration`s like `cmp`, additionally using the fsp as a
+    /// tiebreaker so that otherwise-equal values with different fsp sort
+    /// deterministically. The existing `Ord` still treats them as equal.
+    pub fn cmp_with_fsp(self, other: &Duration) -> Ordering {
+        self.cmp(other).then(self.fsp().cmp(&other.fsp()))
+    }
+
+    /// Formats the duration for report-style output with configurable unit
+    /// labels and separators, e.g. `"838h 00m 00s"`. The fractional part is
+    /// attached to the seconds field according to the fsp.
+    pub fn format_locale(self, opts: &LocaleOpts<'_>) -> String {
+        use std::fmt::Write;
+        let mut string = String::new();
+        if self.get_neg() {
+            string.push('-');
+        }
+
+        write!(
+            &mut string,
+            "{:02}{}{}{:02}{}{}{:02}",
+            self.hours(),
+            opts.hour_unit,
+            opts.separator,
+            self.minutes(),
+            opts.minute_unit,
+            opts.separator,
+            self.secs()
+        )
+        .unwrap();
+
+        let fsp = usize::from(self.fsp());
+        if fsp > 0 {
+            write!(
+                &mut string,
+                ".{:0width$}",
+                self.micros() / TEN_POW[MICRO_WIDTH - fsp],
+                width = fsp
+            )
+            .unwrap();
+        }
+        string.push_str(opts.second_unit);
+
+        string
+    }
+
+    fn write_time_format_segment(self, b: char, output: &mut String) -> Result<()> {
+        use std::fmt::Write;
+        let sign = if self.get_neg() { "-" } else { "" };
+        match b {
+            'H' => {
+                write!(output, "{}{:02}", sign, self.hours()).unwrap();
+            }
+            'k' => {
+                write!(output, "{}{}", sign, self.hours()).unwrap();
+            }
+            'h' | 'I' => {
+                let t = self.hours() % 24;
+                if t % 12 == 0 {
+                    write!(output, "{}12", sign).unwrap();
+                } else {
+                    write!(output, "{}{:02}", sign, t % 12).unwrap();
+                }
+            }
+            'l' => {
+                let t = self.hours() % 24;
+                if t % 12 == 0 {
+                    write!(output, "{}12", sign).unwrap();
+                } else {
+                    write!(output, "{}{}", sign, t % 12).unwrap();
+                }
+            }
+            'i' => {
+                write!(output, "{:02}", self.minutes()).unwrap();
+            }
+            'p' => {
+                if (self.hours() % 24) < 12 {
+                    output.push_str("AM")
+                } else {
+                    output.push_str("PM")
+                }
+            }
+            'r' => {
+                let t = self.hours() % 24;
+                let meridiem = if t < 12 { "AM" } else { "PM" };
+                let h = if t % 12 == 0 { 12 } else { t % 12 };
+                write!(
+                    output,
+                    "{}{:02}:{:02}:{:02} {}",
+                    sign,
+                    h,
+                    self.minutes(),
+                    self.secs(),
+                    meridiem
+                )
+                .unwrap();
+            }
+            'T' => {
+                write!(
+                    output,
+                    "{}{:02}:{:02}:{:02}",
+                    sign,
+                    self.hours(),
+                    self.minutes(),
+                    self.secs()
+                )
+                .unwrap();
+            }
+            'S' | 's' => {
+                write!(output, "{:02}", self.secs()).unwrap();
+            }
+            'f' => {
+                write!(output, "{:06}", self.subsec_micros()).unwrap();
+            }
+            // A duration carries no date part: name specifiers cannot be
+            // rendered at all, while the numeric ones come out as the zero
+            // date, matching MySQL's TIME_FORMAT.
+            'b' | 'M' | 'a' | 'W' => return Err(invalid_type!("invalid time format")),
+            'Y' => output.push_str("0000"),
+            'j' => output.push_str("000"),
+            'm' | 'd' | 'y' | 'U' | 'u' | 'V' | 'v' | 'X' | 'x' => output.push_str("00"),
+            'c' | 'e' | 'w' => output.push('0'),
+            'D' => output.push_str("0th"),
+            _ => output.push(b),
+        }
+        Ok(())
+    }
+
+    /// Formats the duration with a MySQL `TIME_FORMAT` layout string. The
+    /// specifiers mirror `Time::date_format` where they make sense for a
+    /// time-of-day value; `%H`/`%k` render the full hour count (which may
+    /// exceed 23) and carry the sign for negative durations.
+    pub fn time_format(self, layout: &str) -> Result<String> {
+        let mut ret = String::new();
+        let mut pattern_match = false;
+        for b in layout.chars() {
+            if pattern_match {
+                self.write_time_format_segment(b, &mut ret)?;
+                pattern_match = false;
+                continue;
+            }
+            if b == '%' {
+                pattern_match = true;
+            } else {
+                ret.push(b);
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Converts to a `Decimal` holding `HHMMSS[.fraction]` by building the
+    /// scaled integer directly, skipping the format-then-parse round trip
+    /// `TryFrom<Duration> for Decimal` takes. The output matches `TryFrom`
+    /// exactly, including the fraction width.
+    pub fn to_decimal_exact(self) -> Result<Decimal> {
+        let fsp = usize::from(self.fsp());
+        let mut value = i64::from(self.hours()) * 10_000
+            + i64::from(self.minutes()) * 100
+            + i64::from(self.secs());
+
+        if fsp > 0 {
+            value = value * i64::from(TEN_POW[fsp])
+                + i64::from(self.micros() / TEN_POW[MICRO_WIDTH - fsp]);
+        }
+        if self.get_neg() {
+            value = -value;
+        }
+
+        let dec = Decimal::from(value);
+        if fsp == 0 {
+            return Ok(dec);
+        }
+        dec.shift(-(fsp as isize)).into()
+    }
+
+    /// Renders the value as a SQL literal ready to embed in generated text:
+    /// the single-quoted string form `'12:34:56.789'` when `quoted`, or the
+    /// bare numeric form `123456.789` otherwise. The output contains only
+    /// digits, `:`, `.` and the sign, so no escaping is needed.
+    pub fn to_sql_literal(self, quoted: bool) -> String {
+        if quoted {
+            format!("'{}'", self.format(":"))
+        } else {
+            self.format("")
+        }
+    }
+
+    /// Writes the `HHMMSS[.ffffff]` digit bytes (with sign) straight into
+    /// `buf` for `Decimal::from_bytes` to consume, sparing the intermediate
+    /// `String` the `format`-then-parse path allocates.
+    fn write_decimal_digits(self, buf: &mut Vec<u8>) {
+        if self.get_neg() {
+            buf.push(b'-');
+        }
+
+        write!(
+            buf,
+            "{:02}{:02}{:02}",
+            self.hours(),
+            self.minutes(),
+            self.secs()
+        )
+        .unwrap();
+
+        let fsp = usize::from(self.fsp());
+        if fsp > 0 {
+            write!(
+                buf,
+                ".{:0width$}",
+                self.micros() / TEN_POW[MICRO_WIDTH - fsp],
+                width = fsp
+            )
+            .unwrap();
+        }
+    }
+
+    /// Renders the time right-aligned in a cell of `width` display columns
+    /// for tabular CLI output. A value too wide for the cell is truncated
+    /// with a trailing `…`; the formatted text itself is all ASCII, so
+    /// display width equals character count.
+    pub fn to_table_cell(self, width: usize) -> String {
+        let formatted = self.format(":");
+        if formatted.len() <= width {
+            return format!("{:>width$}", formatted, width = width);
+        }
+        if width == 0 {
+            return String::new();
+        }
+
+        let mut cell: String = formatted.chars().take(width - 1).collect();
+        cell.push('…');
+        cell
+    }
+
+    /// Renders the `HH:MM:SS[.ffffff]` form into a stack-backed buffer for
+    /// the encoder hot path. The widest rendering (`-838:59:59.999999`) is
+    /// 17 bytes, so the inline capacity of 18 never spills to the heap.
+    pub fn fmt_bytes(self) -> SmallVec<[u8; 18]> {
+        let mut buf = SmallVec::new();
+        if self.get_neg() {
+            buf.push(b'-');
+        }
+
+        let hours = self.hours();
+        if hours >= 100 {
+            buf.push(b'0' + (hours / 100) as u8);
+        }
+        buf.push(b'0' + (hours / 10 % 10) as u8);
+        buf.push(b'0' + (hours % 10) as u8);
+
+        for &field in &[self.minutes(), self.secs()] {
+            buf.push(b':');
+            buf.push(b'0' + (field / 10) as u8);
+            buf.push(b'0' + (field % 10) as u8);
+        }
+
+        let fsp = usize::from(self.fsp());
+        if fsp > 0 {
+            buf.push(b'.');
+            let frac = self.micros() / TEN_POW[MICRO_WIDTH - fsp];
+            for i in (0..fsp).rev() {
+                buf.push(b'0' + (frac / TEN_POW[i] % 10) as u8);
+            }
+        }
+
+        buf
+    }
+
+    fn format(self, sep: &str) -> String {
+        use std::fmt::Write;
+        let mut string = String::new();
+        if self.get_neg() {
+            string.push('-');
+        }
+
+        write!(
+            &mut string,
+            "{:02}{}{:02}{}{:02}",
+            self.hours(),
+            sep,
+            self.minutes(),
+            sep,
+            self.secs()
+        )
+        .unwrap();
+
+        let fsp = usize::from(self.fsp());
+
+        if self.fsp() > 0 {
+            write!(
+                &mut string,
+                ".{:0width$}",
+                self.micros() / TEN_POW[MICRO_WIDTH - fsp],
+                width = fsp
+            )
+            .unwrap();
+        }
+
+        string
+    }
+}
+
+/// How `parse_with_policy` treats values that parse cleanly but exceed the
+/// TIME range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OverflowPolicy {
+    /// Reject out-of-range values, `parse`'s behavior.
+    Error,
+    /// Clamp to `±838:59:59` (plus the widest fraction at the fsp).
+    Saturate,
+    /// Fold the overflowing hours modulo 24 back into range.
+    WrapDays,
+}
+
+/// The result of `Duration::add_full_report`: the saturated sum plus the
+/// individual facts the reporting add variants expose.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AddReport {
+    /// The sum, saturated to the `Duration` range on overflow.
+    pub value: Duration,
+    /// Whether the addition left the `Duration` range.
+    pub overflowed: bool,
+    /// Whether the result fsp is wider than the left operand's.
+    pub fsp_widened: bool,
+    /// Signed whole days the result spills past 24 hours.
+    pub days: i64,
+}
+
+/// Options controlling `Duration::format_locale`: the unit label appended to
+/// each field and the separator placed between fields.
+#[derive(Clone, Debug)]
+pub struct LocaleOpts<'a> {
+    pub hour_unit: &'a str,
+    pub minute_unit: &'a str,
+    pub second_unit: &'a str,
+    pub separator: &'a str,
+}
+
+/// Computes MySQL's `TIMEDIFF` for two values that are already TIME,
+/// saturating at `±838:59:59.999999` (at the result fsp) instead of erroring
+/// and widening to the larger fsp of the two operands.
+pub fn timediff_time(a: Duration, b: Duration) -> Duration {
+    a.checked_sub(b)
+        .unwrap_or_else(|| Duration::saturate(a < b, a.fsp().max(b.fsp())))
+}
+
+/// A reusable front end for parsing many TIME strings with a fixed fsp,
+/// e.g. when casting a whole string column.
+///
+/// The fsp is validated once at construction instead of per cell; the parser
+/// itself is stateless, so results are identical to `Duration::parse`.
+pub struct DurationParser {
+    fsp: u8,
+}
+
+impl DurationParser {
+    pub fn new(fsp: i8) -> Result<DurationParser> {
+        Ok(DurationParser {
+            fsp: check_fsp(fsp)?,
+        })
+    }
+
+    pub fn parse(&mut self, input: &[u8]) -> Result<Duration> {
+        Duration::parse(input, self.fsp as i8)
+    }
+}
+
+impl Duration {
+    /// Parses a batch of cells into `out`, the batch-oriented front door for
+    /// columnar casts. `out` is cleared and refilled with one result per
+    /// input cell, so a bad cell fails element-wise instead of aborting the
+    /// batch; only an invalid `fsp` fails the call as a whole.
+    pub fn parse_into_column(
+        inputs: &[&[u8]],
+        fsp: i8,
+        out: &mut Vec<Result<Duration>>,
+    ) -> Result<()> {
+        let mut parser = DurationParser::new(fsp)?;
+        out.clear();
+        out.reserve(inputs.len());
+        for input in inputs {
+            out.push(parser.parse(input));
+        }
+        Ok(())
+    }
+}
+
+impl Default for Duration {
+    fn default() -> Duration {
+        Duration::zero()
+    }
+}
+
+impl TryFrom<Duration> for Decimal {
+    type Error = crate::coprocessor::codec::Error;
+    fn try_from(duration: Duration) -> Result<Decimal> {
+        let mut buf = Vec::with_capacity(17);
+        duration.write_decimal_digits(&mut buf);
+        Decimal::from_bytes(&buf)?.into()
+    }
+}
+
+impl Display for Duration {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.format(":"))
+    }
+}
+
+impl PartialEq for Duration {
+    fn eq(&self, dur: &Duration) -> bool {
+        let (mut a, mut b) = (*self, *dur);
+        a.set_fsp(0);
+        b.set_fsp(0);
+        a.0 == b.0
+    }
+}
+
+impl std::hash::Hash for Duration {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let mut inner = *self;
+        inner.set_fsp(0);
+        inner.to_bits().hash(state)
+    }
+}
+
+impl PartialOrd for Duration {
+    fn partial_cmp(&self, dur: &Duration) -> Option<Ordering> {
+        let mut a = *self;
+        let mut b = *dur;
+
+        a.set_fsp(0);
+        b.set_fsp(0);
+
+        let ordering = a.0.cmp(&b.0);
+        Some(if a.get_neg() || b.get_neg() {
+            ordering.reverse()
+        } else {
+            ordering
+        })
+    }
+}
+
+impl Eq for Duration {}
+
+impl Ord for Duration {
+    fn cmp(&self, dur: &Duration) -> Ordering {
+        self.partial_cmp(dur).unwrap()
+    }
+}
+
+impl<T: Write> DurationEncoder for T {}
+pub trait DurationEncoder: NumberEncoder {
+    fn encode_duration(&mut self, v: Duration) -> Result<()> {
+        self.encode_i64(v.to_nanos())?;
+        self.encode_i64(i64::from(v.get_fsp())).map_err(From::from)
+    }
+
+    /// Writes the quoted string form of `v` (e.g. `"12:34:56.789"`) directly
+    /// into the buffer, matching the JSON string representation without
+    /// constructing an intermediate `Json` value.
+    fn encode_duration_json(&mut self, v: Duration) -> Result<()> {
+        self.write_all(b"\"")?;
+        self.write_all(v.format(":").as_bytes())?;
+        self.write_all(b"\"").map_err(From::from)
+    }
+
+    /// Encodes only the comparable nanos `i64`, dropping the per-row fsp
+    /// word `encode_duration` writes, for columns whose fsp is fixed by the
+    /// schema; `Duration::decode_no_fsp` restores it externally. Halves the
+    /// per-row overhead.
+    fn encode_duration_no_fsp(&mut self, v: Duration) -> Result<()> {
+        self.encode_i64(v.to_nanos()).map_err(From::from)
+    }
+
+    /// Delta codec for sorted or clustered TIME columns: writes only the
+    /// varint-encoded difference in micros from the previous value, which
+    /// shrinks near-sorted runs to a byte or two per cell. The fsp is not
+    /// stored; `Duration::decode_delta` carries it over from the previous
+    /// value, so a run must share one fsp (as a column does).
+    fn encode_duration_delta(&mut self, prev: Duration, cur: Duration) -> Result<()> {
+        let delta = cur.to_nanos() / 1000 - prev.to_nanos() / 1000;
+        self.encode_var_i64(delta).map_err(From::from)
+    }
+
+    /// Encodes `v` in the TiDB v2 duration layout: the value as whole
+    /// nanoseconds in a little-endian `i64` followed by a single fsp byte,
+    /// 9 bytes total. This differs from `encode_duration`, which writes
+    /// memcomparable big-endian nanos plus the fsp widened to an `i64`.
+    fn encode_duration_tidb_v2(&mut self, v: Duration) -> Result<()> {
+        self.encode_i64_le(v.to_nanos())?;
+        self.write_all(&[v.fsp()]).map_err(From::from)
+    }
+}
+
+impl Duration {
+    /// Decodes a `Duration` encoded by `encode_duration_no_fsp`, with the
+    /// fsp supplied from schema metadata.
+    pub fn decode_no_fsp(data: &mut BytesSlice<'_>, fsp: i8) -> Result<Duration> {
+        let nanos = number::decode_i64(data)?;
+        Duration::from_nanos(nanos, fsp)
+    }
+
+    /// Decodes a `Duration` encoded by `encode_duration_delta`, given the
+    /// previous value in the run; the fsp carries over from `prev`.
+    pub fn decode_delta(data: &mut BytesSlice<'_>, prev: Duration) -> Result<Duration> {
+        let delta = number::decode_var_i64(data)?;
+        Duration::from_micros(prev.to_nanos() / 1000 + delta, prev.fsp() as i8)
+    }
+
+    /// Decodes a `Duration` encoded by `encode_duration_tidb_v2`.
+    pub fn decode_tidb_v2(data: &mut BytesSlice<'_>) -> Result<Duration> {
+        let nanos = number::decode_i64_le(data)?;
+        let fsp = number::read_u8(data)?;
+        Duration::from_nanos(nanos, fsp as i8)
+    }
+
+    /// `decode` decodes duration encoded by `encode_duration`.
+    pub fn decode(data: &mut BytesSlice<'_>) -> Result<Duration> {
+        let nanos = number::decode_i64(data)?;
+        let fsp = number::decode_i64(data)?;
+        Duration::from_nanos(nanos, fsp as i8)
+    }
+}
+
+impl crate::coprocessor::codec::data_type::AsMySQLBool for Duration {
+    #[inline]
+    fn as_mysql_bool(
+        &self,
+        _context: &mut crate::coprocessor::dag::expr::EvalContext,
+    ) -> crate::coprocessor::Result<bool> {
+        Ok(!self.is_zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64;
+
+    #[test]
+    fn test_hours() {
+        let cases: Vec<(&str, i8, u32)> = vec![
+            ("31 11:30:45", 0, 31 * 24 + 11),
+            ("11:30:45", 0, 11),
+            ("-11:30:45.9233456", 0, 11),
+            ("272:59:59", 0, 272),
+        ];
+
+        for (input, fsp, exp) in cases {
+            let dur = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let res = dur.hours();
+            assert_eq!(exp, res);
+        }
+    }
+
+    #[test]
+    fn test_hours_of_day() {
+        let cases: Vec<(&str, u32)> = vec![
+            ("1 10:11:12", 10),
+            ("11:30:45", 11),
+            ("24:00:00", 0),
+            ("-34:11:12", 10),
+        ];
+
+        for (input, exp) in cases {
+            let dur = Duration::parse(input.as_bytes(), 0).unwrap();
+            assert_eq!(exp, dur.hours_of_day());
+        }
+    }
+
+    #[test]
+    fn test_minutes() {
+        let cases: Vec<(&str, i8, u32)> = vec![
+            ("31 11:30:45", 0, 30),
+            ("11:30:45", 0, 30),
+            ("-11:30:45.9233456", 0, 30),
+        ];
+
+        for (input, fsp, exp) in cases {
+            let dur = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let res = dur.minutes();
+            assert_eq!(exp, res);
+        }
+    }
+
+    #[test]
+    fn test_secs() {
+        let cases: Vec<(&str, i8, u32)> = vec![
+            ("31 11:30:45", 0, 45),
+            ("11:30:45", 0, 45),
+            ("-11:30:45.9233456", 1, 45),
+            ("-11:30:45.9233456", 0, 46),
+        ];
+
+        for (input, fsp, exp) in cases {
+            let dur = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let res = dur.secs();
+            assert_eq!(exp, res);
+        }
+    }
+
+    #[test]
+    fn test_micros() {
+        let cases: Vec<(&str, i8, u32)> = vec![
+            ("31 11:30:45.123", 6, 123000),
+            ("11:30:45.123345", 3, 123000),
+            ("11:30:45.123345", 5, 123350),
+            ("11:30:45.123345", 6, 123345),
+            ("11:30:45.1233456", 6, 123346),
+            ("11:30:45.9233456", 0, 0),
+            ("11:30:45.000010", 6, 10),
+            ("11:30:45.00010", 5, 100),
+            ("11:30:45.1234565", 6, 123457),
+            ("11:30:45.12345649999", 6, 123456),
+            ("11:30:45.0999999", 6, 100000),
+            ("11:30:45.9999994", 6, 999999),
+        ];
+
+        for (input, fsp, exp) in cases {
+            let dur = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let res = dur.micros();
+            assert_eq!(exp, res);
+        }
+    }
+
+    #[test]
+    fn test_subsec_nanos() {
+        // The parser keeps full nanosecond precision, so rounding to the fsp
+        // happens exactly once and the stored fraction carries no significant
+        // digits past it.
+        let cases: Vec<(&str, i8, u32)> = vec![
+            ("11:30:45.1234565", 6, 123_457_000),
+            ("1 10:11:12.1234565", 6, 123_457_000),
+            ("11:30:45.123456789", 6, 123_457_000),
+            ("11:30:45.12345649999", 6, 123_456_000),
+            ("11:30:45.5", 1, 500_000_000),
+            ("11:30:45", 0, 0),
+        ];
+
+        for (input, fsp, exp) in cases {
+            let dur = Duration::parse(input.as_bytes(), fsp).unwrap();
+            assert_eq!(exp, dur.subsec_nanos());
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let cases: Vec<(&'static [u8], i8, Option<&'static str>)> = vec![
+            (b"10:11:12", 0, Some("10:11:12")),
+            (b"101112", 0, Some("10:11:12")),
+            (b"10:11", 0, Some("10:11:00")),
+            (b"101112.123456", 0, Some("10:11:12")),
+            (b"1112", 0, Some("00:11:12")),
+            (b"12", 0, Some("00:00:12")),
+            (b"1 12", 0, Some("36:00:00")),
+            (b"1 10:11:12", 0, Some("34:11:12")),
+            (b"1 10:11:12.123456", 0, Some("34:11:12")),
+            (b"1 10:11:12.123456", 4, Some("34:11:12.1235")),
+            (b"1 10:11:12.12", 4, Some("34:11:12.1200")),
+            (b"1 10:11:12.1234565", 6, Some("34:11:12.123457")),
+            (b"1 10:11:12.12345649999", 6, Some("34:11:12.123456")),
+            (b"1 10:11:12.9999995", 6, Some("34:11:13.000000")),
+            (b"1 10:11:12.0999999", 6, Some("34:11:12.100000")),
+            (b"00:00:00.99999949999", 6, Some("00:00:00.999999")),
+            (b"1 10:11:12.123456", 7, None),
+            (b"10:11:12.123456", 0, Some("10:11:12")),
+            (b"1 10:11", 0, Some("34:11:00")),
+            (b"1 10", 0, Some("34:00:00")),
+            (b"24 10", 0, Some("586:00:00")),
+            (b"-24 10", 0, Some("-586:00:00")),
+            (b"0 10", 0, Some("10:00:00")),
+            (b"-10:10:10", 0, Some("-10:10:10")),
+            (b"-838:59:59", 0, Some("-838:59:59")),
+            (b"838:59:59", 0, Some("838:59:59")),
+            (b"23:60:59", 0, None),
+            (b"54:59:59", 0, Some("54:59:59")),
+            (b"2011-11-11 00:00:01", 0, None),
+            (b"2011-11-11", 0, None),
+            (b"+11:30:45", 0, Some("11:30:45")),
+            (b"+ 1 2:3:4", 0, Some("26:03:04")),
+            (b"+.123", 3, Some("00:00:00.123")),
+            (b"++11", 0, None),
+            (b"+-11", 0, None),
+            (b"--23", 0, None),
+            (b"232 10", 0, None),
+            (b"-232 10", 0, None),
+            (b"00:00:00.1", 0, Some("00:00:00")),
+            (b"00:00:00.1", 1, Some("00:00:00.1")),
+            (b"00:00:00.777777", 2, Some("00:00:00.78")),
+            (b"00:00:00.777777", 6, Some("00:00:00.777777")),
+            (b"00:00:00.001", 3, Some("00:00:00.001")),
+            // NOTE: The following case is easy to fail.
+            (b"- 1 ", 0, Some("-00:00:01")),
+            (b"1:2:3", 0, Some("01:02:03")),
+            (b"1 1:2:3", 0, Some("25:02:03")),
+            (b"-1 1:2:3.123", 3, Some("-25:02:03.123")),
+            (b"-.123", 3, Some("-00:00:00.123")),
+            (b"12345", 0, Some("01:23:45")),
+            (b"-123", 0, Some("-00:01:23")),
+            (b"-23", 0, Some("-00:00:23")),
+            (b"- 1 1", 0, Some("-25:00:00")),
+            (b"-1 1", 0, Some("-25:00:00")),
+            (b" - 1:2:3 .123 ", 3, Some("-01:02:03.123")),
+            (b" - 1 :2 :3 .123 ", 3, Some("-01:02:03.123")),
+            (b" - 1 : 2 :3 .123 ", 3, Some("-01:02:03.123")),
+            (b" - 1 : 2 :  3 .123 ", 3, Some("-01:02:03.123")),
+            (b" - 1 .123 ", 3, Some("-00:00:01.123")),
+            (b"-", 0, None),
+            (b"", 0, None),
+            (b"18446744073709551615:59:59", 0, None),
+            (b"1::2:3", 0, None),
+            (b"1.23 3", 0, None),
+        ];
+
+        for (input, fsp, expect) in cases {
+            let got = Duration::parse(input, fsp);
+
+            if let Some(expect) = expect {
+                assert_eq!(
+                    expect,
+                    &format!(
+                        "{}",
+                        got.unwrap_or_else(|_| panic!(str::from_utf8(input).unwrap().to_string()))
+                    )
+                );
+            } else {
+                assert!(
+                    got.is_err(),
+                    format!(
+                        "{} should not be passed, got {:?}",
+                        str::from_utf8(input).unwrap(),
+                        got
+                    )
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_decimal() {
+        let cases = vec![
+            ("31 11:30:45", 0, "7553045"),
+            ("31 11:30:45", 6, "7553045.000000"),
+            ("31 11:30:45", 0, "7553045"),
+            ("31 11:30:45.123", 6, "7553045.123000"),
+            ("11:30:45", 0, "113045"),
+            ("11:30:45", 6, "113045.000000"),
+            ("11:30:45.123", 6, "113045.123000"),
+            ("11:30:45.123345", 0, "113045"),
+            ("11:30:45.123345", 3, "113045.123"),
+            ("11:30:45.123345", 5, "113045.12335"),
+            ("11:30:45.123345", 6, "113045.123345"),
+            ("11:30:45.1233456", 6, "113045.123346"),
+            ("11:30:45.9233456", 0, "113046"),
+            ("-11:30:45.9233456", 0, "-113046"),
+        ];
+
+        for (input, fsp, exp) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let res = format!("{}", Decimal::try_from(t).unwrap());
+            assert_eq!(exp, res);
+        }
+    }
+
+    #[test]
+    fn test_round_frac() {
+        let cases = vec![
+            ("11:30:45.123456", 4, "11:30:45.1235"),
+            ("11:30:45.123456", 6, "11:30:45.123456"),
+            ("11:30:45.123456", 0, "11:30:45"),
+            ("11:59:59.999999", 3, "12:00:00.000"),
+            ("1 11:30:45.123456", 1, "35:30:45.1"),
+            ("1 11:30:45.999999", 4, "35:30:46.0000"),
+            ("-1 11:30:45.999999", 0, "-35:30:46"),
+            ("-1 11:59:59.9999", 2, "-36:00:00.00"),
+        ];
+        for (input, fsp, exp) in cases {
+            let t = Duration::parse(input.as_bytes(), MAX_FSP)
+                .unwrap()
+                .round_frac(fsp)
+                .unwrap();
+            let res = format!("{}", t);
+            assert_eq!(exp, res);
+        }
+    }
+
+    #[test]
+    fn test_codec() {
+        let cases = vec![
+            ("11:30:45.123456", 4),
+            ("11:30:45.123456", 6),
+            ("11:30:45.123456", 0),
+            ("11:59:59.999999", 3),
+            ("1 11:30:45.123456", 1),
+            ("1 11:30:45.999999", 4),
+            ("-1 11:30:45.999999", 0),
+            ("-1 11:59:59.9999", 2),
+        ];
+        for (input, fsp) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let mut buf = vec![];
+            buf.encode_duration(t).unwrap();
+            let got = Duration::decode(&mut buf.as_slice()).unwrap();
+            assert_eq!(t, got);
+        }
+    }
+
+    #[test]
+    fn test_codec_tidb_v2() {
+        let cases = vec![
+            ("11:30:45.123456", 4),
+            ("11:30:45.123456", 6),
+            ("11:30:45.123456", 0),
+            ("1 11:30:45.999999", 4),
+            ("-1 11:30:45.999999", 0),
+        ];
+        for (input, fsp) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let mut buf = vec![];
+            buf.encode_duration_tidb_v2(t).unwrap();
+            assert_eq!(buf.len(), 9);
+            let got = Duration::decode_tidb_v2(&mut buf.as_slice()).unwrap();
+            assert_eq!(t, got);
+            assert_eq!(t.fsp(), got.fsp());
+        }
+
+        // byte vector produced by TiDB for `11:30:45.123456` at fsp 6
+        let tidb_bytes: [u8; 9] = [0, 124, 220, 177, 177, 37, 0, 0, 6];
+        let got = Duration::decode_tidb_v2(&mut tidb_bytes.as_ref()).unwrap();
+        assert_eq!(got.to_string(), "11:30:45.123456");
+
+        let mut buf = vec![];
+        buf.encode_duration_tidb_v2(got).unwrap();
+        assert_eq!(buf.as_slice(), tidb_bytes.as_ref());
+    }
+
+    #[test]
+    fn test_checked_add_and_sub_duration() {
+        /// `MAX_TIME_IN_SECS` is the maximum for mysql time type.
+        const MAX_TIME_IN_SECS: i64 =
+            (MAX_HOURS * SECS_PER_HOUR + MAX_MINUTES * SECS_PER_MINUTE + MAX_SECONDS) as i64;
+
+        let cases = vec![
+            ("11:30:45.123456", "00:00:14.876545", "11:31:00.000001"),
+            ("11:30:45.123456", "00:30:00", "12:00:45.123456"),
+            ("11:30:45.123456", "12:30:00", "1 00:00:45.123456"),
+            ("11:30:45.123456", "1 12:30:00", "2 00:00:45.123456"),
+        ];
+        for (lhs, rhs, exp) in cases.clone() {
+            let lhs = Duration::parse(lhs.as_bytes(), 6).unwrap();
+            let rhs = Duration::parse(rhs.as_bytes(), 6).unwrap();
+            let res = lhs.checked_add(rhs).unwrap();
+            let exp = Duration::parse(exp.as_bytes(), 6).unwrap();
+            assert_eq!(res, exp);
+        }
+        for (exp, rhs, lhs) in cases {
+            let lhs = Duration::parse(lhs.as_bytes(), 6).unwrap();
+            let rhs = Duration::parse(rhs.as_bytes(), 6).unwrap();
+            let res = lhs.checked_sub(rhs).unwrap();
+            let exp = Duration::parse(exp.as_bytes(), 6).unwrap();
+            assert_eq!(res, exp);
+        }
+
+        let lhs = Duration::parse(b"00:00:01", 6).unwrap();
+        let rhs = Duration::from_nanos(MAX_TIME_IN_SECS * NANOS_PER_SEC, 6).unwrap();
+        assert_eq!(lhs.checked_add(rhs), None);
+        let lhs = Duration::parse(b"-00:00:01", 6).unwrap();
+        let rhs = Duration::from_nanos(MAX_TIME_IN_SECS * NANOS_PER_SEC, 6).unwrap();
+        assert_eq!(lhs.checked_sub(rhs), None);
+    }
+
+    #[test]
+    fn test_from_timecode() {
+        let cases: Vec<(&'static [u8], u32, &'static str)> = vec![
+            (b"01:00:00:12", 24, "01:00:00.500000"),
+            (b"01:00:00:00", 24, "01:00:00.000000"),
+            (b"00:00:00:15", 30, "00:00:00.500000"),
+            (b"838:59:59:23", 24, "838:59:59.958333"),
+        ];
+
+        for (input, fps, exp) in cases {
+            let dur = Duration::from_timecode(input, fps, 6).unwrap();
+            assert_eq!(exp, &format!("{}", dur));
+        }
+
+        // frame out of range
+        assert!(Duration::from_timecode(b"00:00:00:24", 24, 6).is_err());
+        // not a four-field timecode
+        assert!(Duration::from_timecode(b"00:00:00", 24, 6).is_err());
+        assert!(Duration::from_timecode(b"00:00:00:0a", 24, 6).is_err());
+        assert!(Duration::from_timecode(b"00:00:00:00", 0, 6).is_err());
+    }
+
+    #[test]
+    fn test_add_to_time() {
+        let cases = vec![
+            ("2018-01-01 23:30:00", "01:00:00", "2018-01-02 00:30:00"),
+            ("2018-01-31 23:30:00", "01:00:00", "2018-02-01 00:30:00"),
+            ("2018-12-31 23:59:59", "00:00:01", "2019-01-01 00:00:00"),
+            ("2018-01-01 00:30:00", "-01:00:00", "2017-12-31 23:30:00"),
+        ];
+
+        for (datetime, duration, exp) in cases {
+            let datetime = Time::parse_utc_datetime(datetime, 0).unwrap();
+            let duration = Duration::parse(duration.as_bytes(), 0).unwrap();
+            let exp = Time::parse_utc_datetime(exp, 0).unwrap();
+            assert_eq!(exp, duration.add_to_time(datetime).unwrap());
+        }
+
+        let datetime = Time::parse_utc_datetime("9999-12-31 23:59:59", 0).unwrap();
+        let duration = Duration::parse(b"00:00:01", 0).unwrap();
+        assert!(duration.add_to_time(datetime).is_err());
+    }
+
+    #[test]
+    fn test_parse_canonical() {
+        // the neg-zero gap: a negative sub-fsp fraction rounding away
+        let t = Duration::parse(b"-00:00:00.4", 0).unwrap();
+        assert!(t.get_neg());
+        assert!(!t.is_canonical());
+
+        let t = Duration::parse_canonical(b"-00:00:00.4", 0).unwrap();
+        assert!(!t.get_neg());
+        assert!(t.is_canonical());
+        assert_eq!(t, Duration::zero());
+
+        let t = Duration::parse_canonical(b"-00:00:00.0", 0).unwrap();
+        assert!(!t.get_neg());
+        assert!(t.is_canonical());
+
+        // ordinary values come through untouched
+        let t = Duration::parse_canonical(b"-11:30:45.5", 1).unwrap();
+        assert_eq!(t.to_string(), "-11:30:45.5");
+        assert!(t.is_canonical());
+
+        // normalize also repairs crafted representational issues
+        let mut crafted = Duration::parse(b"11:30:45", 0).unwrap();
+        crafted.set_reserved(true);
+        crafted.set_fsp(9);
+        assert!(!crafted.is_canonical());
+        assert!(crafted.normalize().is_canonical());
+    }
+
+    #[test]
+    fn test_total_hours_and_split_days() {
+        let cases = vec![
+            ("50:30:00", 0, 50, (2, "02:30:00")),
+            ("-50:30:00", 0, -50, (-2, "-02:30:00")),
+            ("11:30:45", 0, 11, (0, "11:30:45")),
+            ("838:59:59", 0, 838, (34, "22:59:59")),
+            ("-24:00:00", 0, -24, (-1, "00:00:00")),
+            ("00:00:00", 0, 0, (0, "00:00:00")),
+        ];
+
+        for (input, fsp, hours, (days, rem)) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            assert_eq!(t.total_hours(), hours);
+            let (got_days, got_rem) = t.split_days();
+            assert_eq!((got_days, got_rem.to_string().as_str()), (days, rem));
+        }
+    }
+
+    #[test]
+    fn test_parse_with_policy() {
+        use super::OverflowPolicy::*;
+
+        let cases = vec![
+            ("900:00:00", 0, Error, None),
+            ("900:00:00", 0, Saturate, Some("838:59:59")),
+            ("900:00:00", 0, WrapDays, Some("12:00:00")),
+            ("-900:00:00", 0, Error, None),
+            ("-900:00:00", 0, Saturate, Some("-838:59:59")),
+            ("-900:00:00", 0, WrapDays, Some("-12:00:00")),
+            // a rounding carry past the max is overflow too
+            ("838:59:59.9", 0, Error, None),
+            ("838:59:59.9", 0, Saturate, Some("838:59:59")),
+            ("838:59:59.9", 0, WrapDays, Some("23:00:00")),
+            // in-range values are identical under every policy
+            ("11:30:45.5", 1, Error, Some("11:30:45.5")),
+            ("11:30:45.5", 1, Saturate, Some("11:30:45.5")),
+            ("11:30:45.5", 1, WrapDays, Some("11:30:45.5")),
+            // malformed input errors regardless of policy
+            ("not a time", 0, Saturate, None),
+            ("not a time", 0, WrapDays, None),
+        ];
+
+        for (input, fsp, policy, expected) in cases {
+            let got = Duration::parse_with_policy(input.as_bytes(), fsp, policy);
+            assert_eq!(
+                got.ok().map(|t| t.to_string()),
+                expected.map(str::to_owned),
+                "{} {:?}",
+                input,
+                policy
+            );
+        }
+    }
+
+    #[test]
+    fn test_total_seconds_rounded() {
+        let cases = vec![
+            ("00:00:00.4", 1, 0),
+            ("00:00:00.5", 1, 1),
+            ("00:00:00.6", 1, 1),
+            ("-00:00:00.4", 1, 0),
+            ("-00:00:00.5", 1, -1),
+            ("-00:00:00.6", 1, -1),
+            ("00:00:01.499999", 6, 1),
+            ("00:00:01.500000", 6, 2),
+            ("11:30:45", 0, 41445),
+            ("-11:30:45.5", 1, -41446),
+        ];
+
+        for (input, fsp, expected) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            assert_eq!(t.total_seconds_rounded(), expected);
+        }
+    }
+
+    #[test]
+    fn test_fits_fsp() {
+        let t = Duration::parse(b"00:00:00.120000", 6).unwrap();
+        assert!(t.fits_fsp(2));
+        assert!(t.fits_fsp(3));
+        assert!(!t.fits_fsp(1));
+        assert!(!t.fits_fsp(0));
+
+        let t = Duration::parse(b"11:30:45", 0).unwrap();
+        for fsp in 0..=6 {
+            assert!(t.fits_fsp(fsp));
+        }
+
+        // past MAX_FSP everything trivially fits
+        let t = Duration::parse(b"00:00:00.123456", 6).unwrap();
+        assert!(t.fits_fsp(6));
+        assert!(t.fits_fsp(9));
+    }
+
+    #[test]
+    fn test_codec_no_fsp() {
+        let cases = vec![
+            ("11:30:45.123456", 6),
+            ("-11:30:45.123456", 6),
+            ("838:59:59", 0),
+            ("-1 11:59:59.99", 2),
+        ];
+
+        for (input, fsp) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let mut buf = vec![];
+            buf.encode_duration_no_fsp(t).unwrap();
+            assert_eq!(buf.len(), 8);
+
+            let got = Duration::decode_no_fsp(&mut buf.as_slice(), fsp).unwrap();
+            assert_eq!(t, got);
+            assert_eq!(t.fsp(), got.fsp());
+        }
+    }
+
+    #[test]
+    fn test_random_valid() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut rng: StdRng = SeedableRng::seed_from_u64(0x0d15_ea5e);
+        let max = Duration::parse(b"838:59:59.999999", MAX_FSP).unwrap();
+
+        for _ in 0..1000 {
+            let t = Duration::random_valid(&mut rng, MAX_FSP);
+            assert!(t.is_canonical(), "{:?} not canonical", t);
+            assert!(t.abs() <= max);
+            assert!(t.fsp() <= MAX_FSP as u8);
+
+            // survives a format/parse round trip
+            let reparsed =
+                Duration::parse(t.to_string().as_bytes(), t.fsp() as i8).unwrap();
+            assert_eq!(t, reparsed);
+        }
+
+        // fsp cap is honored (and clamped)
+        for _ in 0..100 {
+            assert_eq!(Duration::random_valid(&mut rng, 0).fsp(), 0);
+            assert!(Duration::random_valid(&mut rng, 9).fsp() <= MAX_FSP as u8);
+        }
+    }
+
+    #[test]
+    fn test_sum_strict_fsp() {
+        let parse = |s: &str, fsp| Duration::parse(s.as_bytes(), fsp).unwrap();
+
+        // precision-compatible values sum fine
+        let values = vec![parse("01:00:00.50", 2), parse("00:30:00.25", 2)];
+        let sum = Duration::sum_strict_fsp(&values, 2).unwrap();
+        assert_eq!(sum.to_string(), "01:30:00.75");
+        assert_eq!(sum.fsp(), 2);
+
+        // an element needing more precision than the target errors
+        let values = vec![parse("01:00:00.5", 1), parse("00:00:00.123", 3)];
+        let err = Duration::sum_strict_fsp(&values, 1).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "00:00:00.123 requires more precision than fsp 1"
+        );
+
+        // overflow errors separately
+        let values = vec![parse("838:59:59", 0), parse("00:00:01", 0)];
+        let err = Duration::sum_strict_fsp(&values, 0).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "duration overflow when summing 00:00:01"
+        );
+    }
+
+    #[test]
+    fn test_to_table_cell() {
+        let t = Duration::parse(b"12:34:56", 0).unwrap();
+        assert_eq!(t.to_table_cell(10), "  12:34:56");
+        assert_eq!(t.to_table_cell(8), "12:34:56");
+
+        // too narrow: truncated with an indicator
+        assert_eq!(t.to_table_cell(6), "12:34…");
+        assert_eq!(t.to_table_cell(1), "…");
+        assert_eq!(t.to_table_cell(0), "");
+
+        let t = Duration::parse(b"-838:59:59.999", 3).unwrap();
+        assert_eq!(t.to_table_cell(16), "  -838:59:59.999");
+        assert_eq!(t.to_table_cell(10), "-838:59:5…");
+    }
+
+    #[test]
+    fn test_parse_cast() {
+        // single rounding: .46 at fsp 0 is 00:00:00 ...
+        let t = Duration::parse_cast(b".46", 1, 0).unwrap();
+        assert_eq!(t.to_string(), "00:00:00");
+
+        // ... while the two-step path rounds twice to 00:00:01
+        let twice = Duration::parse(b".46", 1)
+            .unwrap()
+            .round_frac(0)
+            .unwrap();
+        assert_eq!(twice.to_string(), "00:00:01");
+
+        let t = Duration::parse_cast(b"11:30:45.123456", 6, 2).unwrap();
+        assert_eq!(t.to_string(), "11:30:45.12");
+
+        // both fsp arguments are validated
+        assert!(Duration::parse_cast(b"11:30:45", 7, 0).is_err());
+        assert!(Duration::parse_cast(b"11:30:45", 0, 7).is_err());
+    }
+
+    #[test]
+    fn test_fmt_bytes() {
+        let cases = vec![
+            ("12:34:56", 0),
+            ("-12:34:56", 0),
+            ("838:59:59.999999", 6),
+            ("-838:59:59.999999", 6),
+            ("00:00:00", 0),
+            ("00:00:00.50", 2),
+            ("1 10:11:12.123", 3),
+        ];
+
+        for (input, fsp) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let buf = t.fmt_bytes();
+            assert_eq!(buf.as_slice(), t.format(":").as_bytes());
+            // nothing here is wide enough to leave the stack
+            assert!(!buf.spilled());
+        }
+    }
+
+    #[test]
+    fn test_checked_add_opt() {
+        let parse = |s: &str| Duration::parse(s.as_bytes(), 0).unwrap();
+
+        // folding a nullable column: `None` cells are skipped
+        let column = vec![
+            Some(parse("01:00:00")),
+            None,
+            Some(parse("00:30:00")),
+            None,
+            Some(parse("-00:15:00")),
+        ];
+        let sum = column
+            .into_iter()
+            .try_fold(Duration::zero(), |acc, cell| acc.checked_add_opt(cell))
+            .unwrap();
+        assert_eq!(sum.to_string(), "01:15:00");
+
+        // overflow still propagates as `None`
+        assert_eq!(
+            parse("838:59:59").checked_add_opt(Some(parse("00:00:01"))),
+            None
+        );
+        assert_eq!(
+            parse("838:59:59").checked_add_opt(None),
+            Some(parse("838:59:59"))
+        );
+    }
+
+    #[test]
+    fn test_retag_fsp() {
+        // lossless retags in both directions
+        let t = Duration::parse(b"11:30:45.500000", 6).unwrap();
+        let retagged = t.retag_fsp(1).unwrap();
+        assert_eq!(retagged.to_string(), "11:30:45.5");
+        assert_eq!(retagged.retag_fsp(6).unwrap().to_string(), "11:30:45.500000");
+
+        // a retag that would drop significant digits errors
+        let t = Duration::parse(b"11:30:45.123", 3).unwrap();
+        let err = t.retag_fsp(1).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "retagging 11:30:45.123 to fsp 1 would lose precision"
+        );
+
+        assert!(t.retag_fsp(7).is_err());
+    }
+
+    #[test]
+    fn test_saturating_round_frac() {
+        // the carry past the max saturates instead of erroring
+        let t = Duration::parse(b"838:59:59.9", 1).unwrap();
+        assert!(t.round_frac(0).is_err());
+        assert_eq!(t.saturating_round_frac(0).to_string(), "838:59:59");
+
+        let t = Duration::parse(b"-838:59:59.9", 1).unwrap();
+        assert_eq!(t.saturating_round_frac(0).to_string(), "-838:59:59");
+
+        // in-range rounding matches `round_frac`
+        let t = Duration::parse(b"11:30:45.123456", 6).unwrap();
+        assert_eq!(t.saturating_round_frac(3), t.round_frac(3).unwrap());
+
+        // out-of-range fsp is clamped rather than rejected
+        assert_eq!(t.saturating_round_frac(7), t.round_frac(6).unwrap());
+        assert_eq!(t.saturating_round_frac(-2), t.round_frac(0).unwrap());
+    }
+
+    #[test]
+    fn test_parse_grouped() {
+        let cases = vec![
+            ("1,2345", 0, Some("01:23:45")),
+            ("113,045", 0, Some("11:30:45")),
+            ("-11,30,45", 0, Some("-11:30:45")),
+            // separators not between digits are left for the grammar to
+            // reject
+            (",123", 0, None),
+            ("123,", 0, None),
+            // ungrouped input is unaffected
+            ("11:30:45", 0, Some("11:30:45")),
+        ];
+
+        for (input, fsp, expected) in cases {
+            let got = Duration::parse_grouped(input.as_bytes(), fsp, b',');
+            assert_eq!(got.ok().map(|t| t.to_string()), expected.map(str::to_owned));
+        }
+
+        // a space group separator is refused
+        let err = Duration::parse_grouped(b"1 2345", 0, b' ').unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "group separator ' ' conflicts with the day separator"
+        );
+    }
+
+    #[test]
+    fn test_to_sort_key() {
+        let mut durations: Vec<Duration> = vec![
+            ("838:59:59", 0),
+            ("-838:59:59", 0),
+            ("00:00:00", 0),
+            ("-00:00:00", 6),
+            ("00:00:00.000001", 6),
+            ("-00:00:00.000001", 6),
+            ("11:30:45", 0),
+            ("-11:30:45.5", 1),
+            ("11:30:45.5", 1),
+        ]
+        .into_iter()
+        .map(|(s, fsp)| Duration::parse(s.as_bytes(), fsp).unwrap())
+        .collect();
+
+        durations.sort();
+
+        let mut keys: Vec<u64> = durations.iter().map(|d| d.to_sort_key()).collect();
+        let sorted_keys = {
+            let mut keys = keys.clone();
+            keys.sort();
+            keys
+        };
+        assert_eq!(keys, sorted_keys);
+
+        // equal values map to equal keys regardless of fsp
+        keys.dedup();
+        let mut values = durations.clone();
+        values.dedup();
+        assert_eq!(keys.len(), values.len());
+    }
+
+    #[test]
+    fn test_codec_delta() {
+        let column: Vec<Duration> = vec![
+            "10:00:00.1",
+            "10:00:00.5",
+            "10:00:01.0",
+            "10:00:01.0",
+            "10:05:00.2",
+            "11:00:00.9",
+        ]
+        .into_iter()
+        .map(|s| Duration::parse(s.as_bytes(), 1).unwrap())
+        .collect();
+
+        // encode each cell as a delta from its predecessor (first from zero)
+        let mut buf = vec![];
+        let mut prev = Duration::zero().round_frac(1).unwrap();
+        for &cur in &column {
+            buf.encode_duration_delta(prev, cur).unwrap();
+            prev = cur;
+        }
+
+        // smaller than the independent fixed-width encoding
+        let mut independent = vec![];
+        for &cur in &column {
+            independent.encode_duration(cur).unwrap();
+        }
+        assert!(buf.len() < independent.len());
+
+        let mut slice = buf.as_slice();
+        let mut prev = Duration::zero().round_frac(1).unwrap();
+        for &expected in &column {
+            prev = Duration::decode_delta(&mut slice, prev).unwrap();
+            assert_eq!(prev, expected);
+            assert_eq!(prev.fsp(), expected.fsp());
+        }
+        assert!(slice.is_empty());
+    }
+
+    #[test]
+    fn test_clamp() {
+        let parse = |s: &str| Duration::parse(s.as_bytes(), 0).unwrap();
+
+        let min = parse("-01:00:00");
+        let max = parse("01:00:00");
+
+        assert_eq!(parse("-02:00:00").clamp(min, max), min);
+        assert_eq!(parse("00:30:00").clamp(min, max), parse("00:30:00"));
+        assert_eq!(parse("02:00:00").clamp(min, max), max);
+
+        // negative-only window
+        let min = parse("-03:00:00");
+        let max = parse("-02:00:00");
+        assert_eq!(parse("00:00:00").clamp(min, max), max);
+        assert_eq!(parse("-04:00:00").clamp(min, max), min);
+    }
+
+    #[test]
+    fn test_parse_detecting_fsp() {
+        // sentinel: fsp inferred from the input's fraction digits
+        let cases = vec![
+            ("11:30:45", "11:30:45", 0),
+            ("11:30:45.5", "11:30:45.5", 1),
+            ("11:30:45.123", "11:30:45.123", 3),
+            // more digits than MAX_FSP: capped, excess rounds
+            ("11:30:45.12345649", "11:30:45.123456", 6),
+        ];
+        for (input, expected, fsp) in cases {
+            let (t, used) = Duration::parse_detecting_fsp(input.as_bytes(), UNSPECIFIED_FSP).unwrap();
+            assert_eq!(t.to_string(), expected);
+            assert_eq!(used, fsp);
+        }
+
+        // an explicit fsp wins over the input's digits
+        let (t, used) = Duration::parse_detecting_fsp(b"11:30:45.123", 1).unwrap();
+        assert_eq!(t.to_string(), "11:30:45.1");
+        assert_eq!(used, 1);
+
+        assert!(Duration::parse_detecting_fsp(b"11:30:45", 7).is_err());
+    }
+
+    #[test]
+    fn test_from_packed_decimal() {
+        let cases = vec![
+            ("113045.5", 1, Some("11:30:45.5")),
+            ("-113045.5", 1, Some("-11:30:45.5")),
+            ("113045", 0, Some("11:30:45")),
+            ("0.123456", 6, Some("00:00:00.123456")),
+            ("-0.5", 1, Some("-00:00:00.5")),
+            // half-up boundaries that an f64 round-trip gets wrong next to
+            // seven-digit integer parts
+            ("8385958.1234565", 6, Some("838:59:58.123457")),
+            ("-8385958.1234565", 6, Some("-838:59:58.123457")),
+            ("113045.9999995", 6, Some("11:30:46.000000")),
+            ("8385959", 0, Some("838:59:59")),
+            ("116045", 0, None),
+            ("8395959", 0, None),
+        ];
+
+        for (input, fsp, expected) in cases {
+            let dec: Decimal = input.parse().unwrap();
+            let got = Duration::from_packed_decimal(&dec, fsp);
+            assert_eq!(got.ok().map(|t| t.to_string()), expected.map(str::to_owned));
+
+            // agrees with the string-rendering path where both succeed
+            if let Some(expected) = expected {
+                let via_string = Duration::parse_numeric_decimal(&dec, fsp).unwrap();
+                assert_eq!(via_string.to_string(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sub_reporting() {
+        let parse = |s: &str| Duration::parse(s.as_bytes(), 0).unwrap();
+
+        let cases = vec![
+            ("00:00:05", "00:00:10", Some(("-00:00:05", true))),
+            ("00:00:10", "00:00:05", Some(("00:00:05", false))),
+            ("-00:00:05", "-00:00:10", Some(("00:00:05", true))),
+            ("00:00:05", "00:00:05", Some(("00:00:00", false))),
+            ("-00:00:05", "00:00:05", Some(("-00:00:10", false))),
+            // overflow still yields None
+            ("-838:59:59", "00:00:01", None),
+        ];
+
+        for (lhs, rhs, expected) in cases {
+            let got = parse(lhs).sub_reporting(parse(rhs));
+            assert_eq!(
+                got.map(|(t, flipped)| (t.to_string(), flipped)),
+                expected.map(|(s, flipped)| (s.to_owned(), flipped))
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_sql_literal() {
+        let cases = vec![
+            ("12:34:56.789", 3, "'12:34:56.789'", "123456.789"),
+            ("-12:34:56", 0, "'-12:34:56'", "-123456"),
+            ("838:59:59", 0, "'838:59:59'", "8385959"),
+            ("00:00:00.5", 1, "'00:00:00.5'", "000000.5"),
+        ];
+
+        for (input, fsp, quoted, numeric) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            assert_eq!(t.to_sql_literal(true), quoted);
+            assert_eq!(t.to_sql_literal(false), numeric);
+        }
+    }
+
+    #[test]
+    fn test_parse_max_frac_digits() {
+        let t = Duration::parse_max_frac_digits(b"00:00:00.123", 3, 3).unwrap();
+        assert_eq!(t.to_string(), "00:00:00.123");
+
+        let err = Duration::parse_max_frac_digits(b"00:00:00.1234", 3, 3).unwrap_err();
+        assert_eq!(format!("{}", err), "fractional part has 4 digits (max 3)");
+
+        // no fractional part: nothing to reject
+        let t = Duration::parse_max_frac_digits(b"11:30:45", 3, 0).unwrap();
+        assert_eq!(t.to_string(), "11:30:45.000");
+
+        // fsp still governs the stored precision below the cap
+        let t = Duration::parse_max_frac_digits(b"00:00:00.19", 1, 3).unwrap();
+        assert_eq!(t.to_string(), "00:00:00.2");
+    }
+
+    #[test]
+    fn test_hash_bytes() {
+        // `Eq` values (differing only in fsp) hash to the same bytes
+        let a = Duration::parse(b"11:30:45", 0).unwrap();
+        let b = Duration::parse(b"11:30:45.000000", 6).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.hash_bytes(), b.hash_bytes());
+
+        // unequal values do not
+        let c = Duration::parse(b"11:30:45.5", 1).unwrap();
+        assert_ne!(a, c);
+        assert_ne!(a.hash_bytes(), c.hash_bytes());
+
+        let d = Duration::parse(b"-11:30:45", 0).unwrap();
+        assert_ne!(a.hash_bytes(), d.hash_bytes());
+    }
+
+    #[test]
+    fn test_from_secs() {
+        let cases = vec![
+            (3661, 0, Some("01:01:01")),
+            (-3661, 0, Some("-01:01:01")),
+            (0, 0, Some("00:00:00")),
+            (3_020_399, 0, Some("838:59:59")),
+            (3_020_400, 0, None),
+            (-3_020_400, 0, None),
+        ];
+
+        for (secs, fsp, expected) in cases {
+            let got = Duration::from_secs(secs, fsp);
+            assert_eq!(got.ok().map(|t| t.to_string()), expected.map(str::to_owned));
+        }
+    }
+
+    #[test]
+    fn test_to_millis_f64() {
+        let cases = vec![
+            ("00:00:00.0005", 6, 0.5),
+            ("-00:00:00.0005", 6, -0.5),
+            ("00:00:01.5", 1, 1500.0),
+            ("-00:00:01", 0, -1000.0),
+            ("01:00:00", 0, 3_600_000.0),
+            ("00:00:00", 0, 0.0),
+        ];
+
+        for (input, fsp, expected) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            assert!((t.to_millis_f64() - expected).abs() < f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_parse_digits_colon_only() {
+        let ok_cases = vec![
+            ("-12:34:56.7", 1, "-12:34:56.7"),
+            ("+12:34", 0, "12:34:00"),
+            ("113045", 0, "11:30:45"),
+            ("12", 0, "00:00:12"),
+            ("0.5", 1, "00:00:00.5"),
+        ];
+        for (input, fsp, expected) in ok_cases {
+            let t = Duration::parse_digits_colon_only(input.as_bytes(), fsp).unwrap();
+            assert_eq!(t.to_string(), expected);
+            // agrees with the liberal parser
+            assert_eq!(t, Duration::parse(input.as_bytes(), fsp).unwrap());
+        }
+
+        let err_cases: Vec<&'static [u8]> = vec![
+            b"1 2:3",
+            b" 12:34 ",
+            b"- 1",
+            b"12:34:56:78",
+            b"12:34:56.",
+            b".5",
+            b"12::34",
+            b"12:34.5.6",
+            b"",
+        ];
+        for input in err_cases {
+            assert!(Duration::parse_digits_colon_only(input, 1).is_err());
+        }
+    }
+
+    #[test]
+    fn test_is_canonical() {
+        // everything the public constructors build is canonical
+        for (input, fsp) in vec![
+            ("11:30:45.123456", 6),
+            ("-11:30:45.5", 1),
+            ("838:59:59", 0),
+            ("00:00:00", 0),
+            ("-00:00:00", 0), // neg zero is normalized away by parse
+        ] {
+            assert!(Duration::parse(input.as_bytes(), fsp).unwrap().is_canonical());
+        }
+
+        let base = Duration::parse(b"11:30:45", 0).unwrap();
+
+        let mut crafted = base;
+        crafted.set_reserved(true);
+        assert!(!crafted.is_canonical());
+
+        let mut crafted = base;
+        crafted.set_fsp(MAX_FSP as u8 + 1);
+        assert!(!crafted.is_canonical());
+
+        let mut crafted = base;
+        crafted.set_hours(MAX_HOURS + 1);
+        assert!(!crafted.is_canonical());
+
+        // negative zero
+        assert!(!Duration::new(true, 0, 0, 0, 0, 0).is_canonical());
+
+        // significant fraction digits beyond the fsp
+        assert!(!Duration::new(false, 0, 0, 0, 500_000_000, 0).is_canonical());
+        assert!(Duration::new(false, 0, 0, 0, 500_000_000, 1).is_canonical());
+    }
+
+    #[test]
+    fn test_saturating_mul() {
+        let cases = vec![
+            // in-range products pass through
+            ("11:30:45", 0, 2, "23:01:30"),
+            ("-00:00:00.5", 1, 3, "-00:00:01.5"),
+            // overflow saturates with the product's sign
+            ("500:00:00", 0, 2, "838:59:59"),
+            ("500:00:00", 0, -2, "-838:59:59"),
+            ("-500:00:00.5", 1, 2, "-838:59:59.9"),
+            ("-500:00:00", 0, -2, "838:59:59"),
+            // i64-level overflow saturates too
+            ("838:59:59", 0, i64::max_value(), "838:59:59"),
+            ("838:59:59", 0, i64::min_value(), "-838:59:59"),
+        ];
+
+        for (input, fsp, rhs, expected) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            assert_eq!(t.saturating_mul(rhs).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_rounded_minutes() {
+        let cases = vec![
+            ("12:00:29", 0, 720),
+            ("12:00:29.999999", 6, 720),
+            ("12:00:30", 0, 721),
+            ("12:00:31", 0, 721),
+            ("-12:00:29", 0, -720),
+            ("-12:00:30", 0, -721),
+            ("00:00:29", 0, 0),
+            ("00:00:30", 0, 1),
+            ("838:59:59", 0, 50340),
+        ];
+
+        for (input, fsp, expected) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            assert_eq!(t.rounded_minutes(), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_trailing_sign() {
+        let cases = vec![
+            ("12:00:00-", 0, Some("-12:00:00")),
+            ("12:34:56.5-", 1, Some("-12:34:56.5")),
+            // leading-sign input still works
+            ("-12:00:00", 0, Some("-12:00:00")),
+            ("12:00:00", 0, Some("12:00:00")),
+            // signed zero stays zero
+            ("00:00:00-", 0, Some("00:00:00")),
+            // mixing both signs is rejected
+            ("-12:00:00-", 0, None),
+            ("+12:00:00-", 0, None),
+        ];
+
+        for (input, fsp, expected) in cases {
+            let got = Duration::parse_trailing_sign(input.as_bytes(), fsp);
+            assert_eq!(got.ok().map(|t| t.to_string()), expected.map(str::to_owned));
+        }
+    }
+
+    #[test]
+    fn test_add_full_report() {
+        let parse = |s: &str, fsp| Duration::parse(s.as_bytes(), fsp).unwrap();
+
+        // overflowing add: saturated value, overflow flag, widened fsp
+        let report = parse("838:00:00", 0).add_full_report(parse("10:00:00.5", 1));
+        assert_eq!(report.value.to_string(), "838:59:59.9");
+        assert!(report.overflowed);
+        assert!(report.fsp_widened);
+        assert_eq!(report.days, 34);
+
+        // plain add spilling past one day
+        let report = parse("23:00:00", 0).add_full_report(parse("02:00:00", 0));
+        assert_eq!(report.value.to_string(), "25:00:00");
+        assert!(!report.overflowed);
+        assert!(!report.fsp_widened);
+        assert_eq!(report.days, 1);
+
+        // negative result reports negative days
+        let report = parse("-30:00:00", 0).add_full_report(parse("-30:00:00", 0));
+        assert_eq!(report.value.to_string(), "-60:00:00");
+        assert_eq!(report.days, -2);
+        assert!(!report.overflowed);
+    }
+
+    #[test]
+    fn test_parse_colon_frac() {
+        let cases = vec![
+            ("12:34:56:789", 3, Some("12:34:56.789")),
+            ("-12:34:56:789", 3, Some("-12:34:56.789")),
+            ("12:34:56:789", 2, Some("12:34:56.79")),
+            // no fourth field: plain `parse` semantics
+            ("12:34:56", 0, Some("12:34:56")),
+            ("12:34", 0, Some("12:34:00")),
+            // the strict parser rejects the colon fraction
+            ("12:34:56:65", 2, Some("12:34:56.65")),
+        ];
+
+        for (input, fsp, expected) in cases {
+            let got = Duration::parse_colon_frac(input.as_bytes(), fsp);
+            assert_eq!(got.ok().map(|t| t.to_string()), expected.map(str::to_owned));
+            // the plain parser never accepts the four-field form
+            if input.bytes().filter(|&c| c == b':').count() == 3 {
+                assert!(Duration::parse(input.as_bytes(), fsp).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_to_signed_secs_and_nanos() {
+        let cases = vec![
+            ("-00:00:00.5", 1, (0, -500_000_000)),
+            ("00:00:00.5", 1, (0, 500_000_000)),
+            ("-00:00:01.5", 1, (-1, -500_000_000)),
+            ("11:30:45.123456", 6, (41445, 123_456_000)),
+            ("-838:59:59.999999", 6, (-3_020_399, -999_999_000)),
+            ("00:00:00", 0, (0, 0)),
+        ];
+
+        for (input, fsp, expected) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            assert_eq!(t.to_signed_secs_and_nanos(), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_into_column() {
+        let inputs: Vec<&[u8]> = vec![
+            b"11:30:45.5",
+            b"not a time",
+            b"-1 10:11:12",
+            b"",
+            b"838:59:59",
+        ];
+
+        let mut out = Vec::new();
+        Duration::parse_into_column(&inputs, 1, &mut out).unwrap();
+        assert_eq!(out.len(), inputs.len());
+
+        assert_eq!(out[0].as_ref().unwrap().to_string(), "11:30:45.5");
+        assert!(out[1].is_err());
+        assert_eq!(out[2].as_ref().unwrap().to_string(), "-34:11:12.0");
+        assert!(out[3].is_err());
+        assert_eq!(out[4].as_ref().unwrap().to_string(), "838:59:59.0");
+
+        // the batch is replaced, not appended to
+        Duration::parse_into_column(&inputs[..1], 1, &mut out).unwrap();
+        assert_eq!(out.len(), 1);
+
+        // an invalid fsp fails the whole call
+        assert!(Duration::parse_into_column(&inputs, 7, &mut out).is_err());
+    }
+
+    #[test]
+    fn test_to_decimal_exact() {
+        // the `test_to_decimal` cases: `to_decimal_exact` must match the
+        // string-round-trip `TryFrom` output digit for digit
+        let cases = vec![
+            ("31 11:30:45", 0),
+            ("31 11:30:45", 6),
+            ("31 11:30:45.123", 6),
+            ("11:30:45", 0),
+            ("11:30:45", 6),
+            ("11:30:45.123", 6),
+            ("11:30:45.123345", 0),
+            ("11:30:45.123345", 3),
+            ("11:30:45.123345", 5),
+            ("11:30:45.123345", 6),
+            ("11:30:45.1233456", 6),
+            ("11:30:45.9233456", 0),
+            ("-11:30:45.9233456", 0),
+            ("-838:59:59.999999", 6),
+        ];
+
+        for (input, fsp) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let via_string = format!("{}", Decimal::try_from(t).unwrap());
+            let exact = format!("{}", t.to_decimal_exact().unwrap());
+            assert_eq!(via_string, exact);
+        }
+    }
+
+    #[test]
+    fn test_parse_with_max_len_guard() {
+        let long = vec![b' '; MAX_DURATION_STR_LEN + 1];
+        let err = Duration::parse(&long, 0).unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "time string too long: 65 bytes (max 64)"
+        );
+
+        // a custom cap applies instead of the default
+        assert!(Duration::parse_with_max_len(&long, 0, 128).is_err()); // still not a valid time
+        let err = Duration::parse_with_max_len(b"11:30:45", 0, 4).unwrap_err();
+        assert_eq!(format!("{}", err), "time string too long: 8 bytes (max 4)");
+
+        // inputs at the boundary still go through the grammar
+        let mut padded = vec![b' '; MAX_DURATION_STR_LEN - 8];
+        padded.extend_from_slice(b"11:30:45");
+        assert_eq!(
+            Duration::parse(&padded, 0).unwrap().to_string(),
+            "11:30:45"
+        );
+    }
+
+    #[test]
+    fn test_checked_mul() {
+        let cases = vec![
+            // sub-second carry into the seconds part
+            ("00:00:00.500000", 6, 3.0, Some("00:00:01.500000")),
+            ("00:00:00.999999", 6, 2.0, Some("00:00:01.999998")),
+            ("00:00:59.999999", 6, 2.0, Some("00:01:59.999998")),
+            ("11:30:45", 0, 2.0, Some("23:01:30")),
+            ("-00:00:00.5", 1, 3.0, Some("-00:00:01.5")),
+            ("11:30:45", 0, 0.0, Some("00:00:00")),
+            // fractional factors round half-up at the value's own fsp
+            ("00:00:10", 0, 1.55, Some("00:00:16")),
+            ("00:00:10", 0, 1.54, Some("00:00:15")),
+            ("00:00:01", 6, 0.5, Some("00:00:00.500000")),
+            // non-finite and out-of-range products
+            ("500:00:00", 0, 2.0, None),
+            ("838:59:59", 0, f64::INFINITY, None),
+            ("838:59:59", 0, f64::NAN, None),
+        ];
+
+        for (input, fsp, rhs, expected) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let got = t.checked_mul(rhs);
+            assert_eq!(got.map(|t| t.to_string()), expected.map(str::to_owned));
+        }
+    }
+
+    #[test]
+    fn test_checked_div() {
+        let cases = vec![
+            ("00:00:03", 6, 2.0, Some("00:00:01.500000")),
+            ("00:00:03", 0, 2.0, Some("00:00:02")),
+            ("-01:00:00", 0, 4.0, Some("-00:15:00")),
+            ("11:30:45", 0, 0.5, Some("23:01:30")),
+            // dividing by zero or a tiny factor leaves the range
+            ("00:00:01", 0, 0.0, None),
+            ("838:59:59", 0, 1e-12, None),
+        ];
+
+        for (input, fsp, rhs, expected) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let got = t.checked_div(rhs);
+            assert_eq!(got.map(|t| t.to_string()), expected.map(str::to_owned));
+        }
+    }
+
+    #[test]
+    fn test_to_bits_fsp0() {
+        let cases = vec![
+            ("11:30:45.123456", 6),
+            ("-11:30:45.5", 1),
+            ("838:59:59", 0),
+        ];
+
+        for (input, schema_fsp) in cases {
+            let t = Duration::parse(input.as_bytes(), schema_fsp).unwrap();
+            let bits = t.to_bits_fsp0();
+
+            let stored = Duration::from_bits(bits).unwrap();
+            assert_eq!(stored.fsp(), 0);
+
+            // restore the fsp from schema metadata
+            let micros = stored.to_nanos() / 1000;
+            let restored = Duration::from_micros(micros, schema_fsp).unwrap();
+            assert_eq!(restored, t);
+            assert_eq!(restored.fsp(), t.fsp());
+        }
+    }
+
+    #[test]
+    fn test_overflowing_add_and_sub() {
+        let parse = |s: &str, fsp| Duration::parse(s.as_bytes(), fsp).unwrap();
+
+        // normal cases pass through unchanged
+        let (res, overflowed) = parse("11:30:45", 0).overflowing_add(parse("00:29:15", 0));
+        assert_eq!((res.to_string().as_str(), overflowed), ("12:00:00", false));
+        let (res, overflowed) = parse("11:30:45", 0).overflowing_sub(parse("11:30:45", 0));
+        assert_eq!((res.to_string().as_str(), overflowed), ("00:00:00", false));
+
+        // positive add saturates positively
+        let (res, overflowed) = parse("838:59:59", 0).overflowing_add(parse("00:00:01", 0));
+        assert_eq!((res.to_string().as_str(), overflowed), ("838:59:59", true));
+
+        // negative sub saturates negatively, at the wider fsp
+        let (res, overflowed) = parse("-838:59:59", 0).overflowing_sub(parse("00:00:01.5", 1));
+        assert_eq!(
+            (res.to_string().as_str(), overflowed),
+            ("-838:59:59.9", true)
+        );
+    }
+
+    #[test]
+    fn test_parse_require_seconds() {
+        let ok_cases = vec![
+            ("12:34:56", 0, "12:34:56"),
+            ("-12:34:56.5", 1, "-12:34:56.5"),
+            ("1:2:3", 0, "01:02:03"),
+        ];
+        for (input, fsp, expected) in ok_cases {
+            let t = Duration::parse_require_seconds(input.as_bytes(), fsp).unwrap();
+            assert_eq!(t.to_string(), expected);
+        }
+
+        let err_cases: Vec<&'static [u8]> = vec![b"12:34", b"12", b"123456", b"1 12:34:56"];
+        for input in err_cases {
+            // `parse` accepts all of these...
+            assert!(Duration::parse(input, 0).is_ok());
+            // ...the strict form does not
+            let err = Duration::parse_require_seconds(input, 0).unwrap_err();
+            assert_eq!(format!("{}", err), "seconds field required");
+        }
+    }
+
+    #[test]
+    fn test_value_eq() {
+        let cases = vec![
+            ("12:00:00", 0, "12:00:00", 6, true),
+            ("12:00:00", 0, "12:00:00.000000", 6, true),
+            ("12:00:00.5", 1, "12:00:00.500000", 6, true),
+            ("12:00:00.5", 1, "12:00:00", 0, false),
+            ("-12:00:00", 0, "12:00:00", 0, false),
+            ("00:00:00", 0, "-00:00:00", 6, true),
+        ];
+
+        for (lhs, lfsp, rhs, rfsp, expected) in cases {
+            let lhs = Duration::parse(lhs.as_bytes(), lfsp).unwrap();
+            let rhs = Duration::parse(rhs.as_bytes(), rfsp).unwrap();
+            assert_eq!(lhs.value_eq(rhs), expected);
+            assert_eq!(rhs.value_eq(lhs), expected);
+            // `Eq` agrees
+            assert_eq!(lhs == rhs, expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_unicode_ws() {
+        // NBSP-wrapped and NBSP-separated inputs
+        let cases = vec![
+            ("\u{00A0}12:34:56\u{00A0}", 0, "12:34:56"),
+            ("-\u{00A0}1\u{00A0}2:3:4", 0, "-26:03:04"),
+            ("\u{3000}11:30:45.5\u{2007}", 1, "11:30:45.5"),
+        ];
+
+        for (input, fsp, expected) in cases {
+            // the strict parser rejects them...
+            assert!(Duration::parse(input.as_bytes(), fsp).is_err());
+            // ...the normalizing one accepts them
+            let t = Duration::parse_unicode_ws(input.as_bytes(), fsp).unwrap();
+            assert_eq!(t.to_string(), expected);
+        }
+
+        assert!(Duration::parse_unicode_ws(b"\xff\xfe", 0).is_err());
+    }
+
+    #[test]
+    fn test_add_clock_reporting() {
+        let cases = vec![
+            ("23:00:00", "02:00:00", 0, "01:00:00", 1),
+            ("01:00:00", "-02:00:00", 0, "23:00:00", -1),
+            ("12:00:00", "05:00:00", 0, "17:00:00", 0),
+            ("00:00:00", "-00:00:00.5", 1, "23:59:59.5", -1),
+            ("838:59:59", "838:59:59", 0, "21:59:58", 69),
+            ("-838:59:59", "-838:59:59", 0, "02:00:02", -70),
+        ];
+
+        for (lhs, rhs, fsp, expected, days) in cases {
+            let lhs = Duration::parse(lhs.as_bytes(), fsp).unwrap();
+            let rhs = Duration::parse(rhs.as_bytes(), fsp).unwrap();
+            let (res, got_days) = lhs.add_clock_reporting(rhs);
+            assert_eq!(res.to_string(), expected);
+            assert_eq!(got_days, days);
+        }
+    }
+
+    #[test]
+    fn test_from_micros_const_fsp() {
+        let cases: Vec<i64> = vec![
+            0,
+            1,
+            -1,
+            999_999,
+            1_000_000,
+            -123_456_789,
+            3_020_398_999_999,
+            -3_020_398_999_999,
+        ];
+
+        for micros in cases {
+            assert_eq!(
+                Duration::from_micros_0(micros).unwrap(),
+                Duration::from_micros(micros, 0).unwrap()
+            );
+            assert_eq!(
+                Duration::from_micros_3(micros).unwrap(),
+                Duration::from_micros(micros, 3).unwrap()
+            );
+            assert_eq!(
+                Duration::from_micros_6(micros).unwrap(),
+                Duration::from_micros(micros, 6).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_debug_parse() {
+        let dump = Duration::debug_parse(b"1 12", 0);
+        assert!(dump.contains("day: Some(1)"), "{}", dump);
+        assert!(dump.contains("hour: Some(12)"), "{}", dump);
+        assert!(dump.contains("day 1 folded into hours: 36"), "{}", dump);
+        assert!(dump.contains("parsed: 36:00:00"), "{}", dump);
+
+        let dump = Duration::debug_parse(b"1 ", 0);
+        assert!(
+            dump.contains("block 1 reinterpreted as 00:00:01"),
+            "{}",
+            dump
+        );
+
+        let dump = Duration::debug_parse(b"11:30:45", 0);
+        assert!(dump.contains("plain hh:mm:ss"), "{}", dump);
+
+        let dump = Duration::debug_parse(b"invalid", 0);
+        assert!(dump.contains("error: invalid time format"), "{}", dump);
+    }
+
+    #[test]
+    fn test_sum_to_fsp() {
+        let values: Vec<Duration> = vec!["00:00:00.123456", "00:00:00.654321", "01:00:00.004443"]
+            .into_iter()
+            .map(|s| Duration::parse(s.as_bytes(), 6).unwrap())
+            .collect();
+
+        // rounding happens once on the total (0.782220 → .78), not per element
+        // (0.12 + 0.65 + 0.00 would give .77)
+        let sum = Duration::sum_to_fsp(&values, 2).unwrap();
+        assert_eq!(sum.to_string(), "01:00:00.78");
+        assert_eq!(sum.fsp(), 2);
+
+        assert_eq!(
+            Duration::sum_to_fsp(&[], 3).unwrap(),
+            Duration::zero().round_frac(3).unwrap()
+        );
+
+        let overflow = vec![
+            Duration::parse(b"838:59:59", 0).unwrap(),
+            Duration::parse(b"00:00:01", 0).unwrap(),
+        ];
+        assert!(Duration::sum_to_fsp(&overflow, 0).is_err());
+    }
+
+    #[test]
+    fn test_fixed_bytes() {
+        let cases = vec![
+            ("11:30:45.123456", 6),
+            ("-11:30:45.123456", 6),
+            ("838:59:59", 0),
+            ("-838:59:59.999999", 6),
+            ("00:00:00", 0),
+            ("1 10:11:12.5", 1),
+        ];
+
+        for (input, fsp) in cases {
+            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let buf = t.to_fixed_bytes();
+            let got = Duration::from_fixed_bytes(&buf).unwrap();
+            assert_eq!(t, got);
+            assert_eq!(t.fsp(), got.fsp());
+        }
+
+        // an fsp byte out of range must be rejected
+        let mut buf = Duration::parse(b"11:30:45", 0).unwrap().to_fixed_bytes();
+        buf[8] = MAX_FSP as u8 + 1;
+        assert!(Duration::from_fixed_bytes(&buf).is_err());
+    }
+
+    #[test]
+    fn test_reject_date_literal() {
+        let cases: Vec<&'static [u8]> = vec![
+            b"2011-11-11",
+            b"2011-11-11 10:00:00",
+            b"2011-11-11 00:00:01",
+        ];
+
+        for input in cases {
+            let err = Duration::parse(input, 0).unwrap_err();
+            assert_eq!(format!("{}", err), "date literal not valid as TIME");
+        }
+
+        // signed input is unaffected
+        assert!(Duration::parse(b"-11:30:45", 0).is_ok());
+        assert!(Duration::parse(b" - 1:2:3 ", 0).is_ok());
+    }
+
+    #[test]
+    fn test_format_locale() {
+        let short = LocaleOpts {
+            hour_unit: "h",
+            minute_unit: "m",
+            second_unit: "s",
+            separator: " ",
+        };
+        let long = LocaleOpts {
+            hour_unit: " hours",
+            minute_unit: " minutes",
+            second_unit: " seconds",
+            separator: ", ",
+        };
+
+        let dur = Duration::parse(b"838:00:00", 0).unwrap();
+        assert_eq!("838h 00m 00s", &dur.format_locale(&short));
+        assert_eq!("838 hours, 00 minutes, 00 seconds", &dur.format_locale(&long));
+
+        let dur = Duration::parse(b"-1:02:03.5", 1).unwrap();
+        assert_eq!("-01h 02m 03.5s", &dur.format_locale(&short));
+    }
+
+    #[test]
+    fn test_time_format() {
+        let cases = vec![
+            (
+                "23:12:34.123456",
+                "%H %k %h %I %l %i %p %r %T %s %f %%",
+                "23 23 11 11 11 12 PM 11:12:34 PM 23:12:34 34 123456 %",
+            ),
+            ("150:02:28.5", "%H %k %h %l", "150 150 06 6"),
+            ("-01:02:03", "%H:%i:%s and %T", "-01:02:03 and -01:02:03"),
+            ("00:30:00", "%h %p %r", "12 AM 12:30:00 AM"),
+            ("12:00:00", "%h %p", "12 PM"),
+            ("11:23:45", "%Y-%m-%d %H:%i:%s", "0000-00-00 11:23:45"),
+            ("11:23:45", "%z", "z"),
+        ];
+        for (input, layout, expected) in cases {
+            let dur = Duration::parse(input.as_bytes(), 6).unwrap();
+            assert_eq!(expected, &dur.time_format(layout).unwrap());
+        }
+
+        // there is no month or weekday to spell out
+        let dur = Duration::parse(b"11:23:45", 0).unwrap();
+        for layout in &["%b", "%M", "%a", "%W"] {
+            assert!(dur.time_format(layout).is_err());
+        }
+    }
+
+    #[test]
+    fn test_timediff_time() {
+        // normal difference
+        let a = Duration::parse(b"11:30:45.5", 1).unwrap();
+        let b = Duration::parse(b"00:30:45", 0).unwrap();
+        assert_eq!("11:00:00.5", &format!("{}", timediff_time(a, b)));
+
+        // saturating difference, both directions
+        let a = Duration::parse(b"-838:00:00", 0).unwrap();
+        let b = Duration::parse(b"100:00:00", 0).unwrap();
+        assert_eq!("-838:59:59", &format!("{}", timediff_time(a, b)));
+        assert_eq!("838:59:59", &format!("{}", timediff_time(b, a)));
+
+        let a = Duration::parse(b"-838:00:00", 6).unwrap();
+        let b = Duration::parse(b"100:00:00", 0).unwrap();
+        assert_eq!("-838:59:59.999999", &format!("{}", timediff_time(a, b)));
+    }
+
+    #[test]
+    fn test_from_micros_reporting() {
+        let (dur, modified) = Duration::from_micros_reporting(1_500_000, 0).unwrap();
+        assert_eq!("00:00:02", &format!("{}", dur));
+        assert!(modified);
+
+        let (dur, modified) = Duration::from_micros_reporting(1_000_000, 0).unwrap();
+        assert_eq!("00:00:01", &format!("{}", dur));
+        assert!(!modified);
+
+        let (dur, modified) = Duration::from_micros_reporting(-1_234_567, 6).unwrap();
+        assert_eq!("-00:00:01.234567", &format!("{}", dur));
+        assert!(!modified);
+    }
+
+    #[test]
+    fn test_round_frac_slice() {
+        let mut durations: Vec<Duration> = ["11:30:45.123456", "-11:30:45.9", "00:00:00.5"]
+            .iter()
+            .map(|input| Duration::parse(input.as_bytes(), MAX_FSP).unwrap())
+            .collect();
+        Duration::round_frac_slice(&mut durations, 0).unwrap();
+
+        let exp = ["11:30:45", "-11:30:46", "00:00:01"];
+        for (duration, exp) in durations.iter().zip(exp.iter()) {
+            assert_eq!(*exp, &format!("{}", duration));
+        }
+
+        // the failing index is reported
+        let mut durations = vec![
+            Duration::parse(b"00:00:00", 0).unwrap(),
+            Duration::parse(b"838:59:59.9", 1).unwrap(),
+        ];
+        let err = Duration::round_frac_slice(&mut durations, 0).unwrap_err();
+        assert!(format!("{}", err).starts_with("round_frac failed at index 1"));
+    }
+
+    #[test]
+    fn test_as_micros_saturating() {
+        let cases = vec![
+            ("11:30:45.123456", 6, 41_445_123_456),
+            ("-11:30:45.123456", 6, -41_445_123_456),
+            ("00:00:00", 0, 0),
+        ];
+
+        for (input, fsp, exp) in cases {
+            let dur = Duration::parse(input.as_bytes(), fsp).unwrap();
+            assert_eq!(exp, dur.as_micros_saturating());
+        }
+
+        // A value with all field bits set (far beyond what `from_bits`
+        // accepts) must still be computed without overflowing.
+        let mut crafted = Duration(0);
+        crafted.set_hours(0x3FF);
+        crafted.set_minutes(0x3F);
+        crafted.set_secs(0x3F);
+        crafted.set_nanos(0xFFFF_FFFF);
+        assert_eq!(
+            i64::from(0x3FFu32) * 3600 * 1_000_000
+                + i64::from(0x3Fu32) * 60 * 1_000_000
+                + i64::from(0x3Fu32) * 1_000_000
+                + i64::from(0xFFFF_FFFFu32 / 1000),
+            crafted.as_micros_saturating()
+        );
+    }
+
+    #[test]
+    fn test_parse_skip_bom() {
+        let dur = Duration::parse_skip_bom(b"\xef\xbb\xbf12:34:56", 0).unwrap();
+        assert_eq!("12:34:56", &format!("{}", dur));
+
+        // without a BOM it behaves exactly like `parse`
+        let dur = Duration::parse_skip_bom(b"12:34:56", 0).unwrap();
+        assert_eq!("12:34:56", &format!("{}", dur));
+
+        // strict `parse` keeps rejecting BOM-prefixed input
+        assert!(Duration::parse(b"\xef\xbb\xbf12:34:56", 0).is_err());
+    }
+
+    #[test]
+    fn test_add_clamp_fsp() {
+        let lhs = Duration::parse(b"11:30:45.123456", 6).unwrap();
+        let rhs = Duration::parse(b"00:00:14.876545", 6).unwrap();
+        let res = lhs.add_clamp_fsp(rhs, 2).unwrap();
+        assert_eq!("11:31:00.00", &format!("{}", res));
+
+        let lhs = Duration::parse(b"00:00:00.999", 3).unwrap();
+        let rhs = Duration::parse(b"00:00:00.0006", 4).unwrap();
+        let res = lhs.add_clamp_fsp(rhs, 2).unwrap();
+        assert_eq!("00:00:01.00", &format!("{}", res));
+
+        let lhs = Duration::parse(b"838:59:59", 0).unwrap();
+        let rhs = Duration::parse(b"00:00:01", 0).unwrap();
+        assert!(lhs.add_clamp_fsp(rhs, 0).is_err());
+    }
+
+    #[test]
+    fn test_parse_fixed_hhmmss() {
+        let cases: Vec<(&'static [u8], Option<&'static str>)> = vec![
+            (b"0123045", Some("12:30:45")),
+            (b"123045", Some("12:30:45")),
+            (b"12345", Some("01:23:45")),
+            (b"8385959", Some("838:59:59")),
+            (b"12", Some("00:00:12")),
+            (b"12345678", None),
+            (b"123:045", None),
+            (b"-123045", None),
+            (b"", None),
+        ];
+
+        for (input, expect) in cases {
+            let got = Duration::parse_fixed_hhmmss(input, 0);
+            match expect {
+                Some(expect) => {
+                    let got = got.unwrap();
+                    assert_eq!(expect, &format!("{}", got));
+                    // consistent with the block interpretation of `parse`
+                    assert_eq!(got, Duration::parse(input, 0).unwrap());
+                }
+                None => assert!(got.is_err(), "{:?} should not be passed", input),
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_duration_json() {
+        let cases = vec![("12:34:56.789", 3), ("-1 10:11:12", 0), ("00:00:00", 0)];
+
+        for (input, fsp) in cases {
+            let dur = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let mut buf = vec![];
+            buf.encode_duration_json(dur).unwrap();
+            assert_eq!(format!("\"{}\"", dur).into_bytes(), buf);
+        }
+    }
+
+    #[test]
+    fn test_parse_ctx_reporting() {
+        use crate::coprocessor::dag::expr::EvalContext;
+
+        let mut ctx = EvalContext::default();
+        let (dur, modified) = Duration::parse_ctx_reporting(&mut ctx, b"00:00:00.999", 1).unwrap();
+        assert_eq!("00:00:01.0", &format!("{}", dur));
+        assert!(modified);
+        assert_eq!(1, ctx.warnings.warning_cnt);
+
+        let mut ctx = EvalContext::default();
+        let (dur, modified) = Duration::parse_ctx_reporting(&mut ctx, b"00:00:00.9", 1).unwrap();
+        assert_eq!("00:00:00.9", &format!("{}", dur));
+        assert!(!modified);
+        assert_eq!(0, ctx.warnings.warning_cnt);
+    }
+
+    #[test]
+    fn test_parse_with_ctx() {
+        use crate::coprocessor::dag::expr::{EvalConfig, EvalContext, Flag};
+        use std::sync::Arc;
+
+        // without the flag the fraction is rounded, exactly like `parse`
+        let mut ctx = EvalContext::default();
+        let dur = Duration::parse_with_ctx(&mut ctx, b"00:00:00.46", 1).unwrap();
+        assert_eq!("00:00:00.5", &format!("{}", dur));
+        assert_eq!(0, ctx.warnings.warning_cnt);
+
+        // with TRUNCATE_AS_WARNING the digits past fsp are dropped and a
+        // warning is recorded
+        let cfg = Arc::new(EvalConfig::from_flag(Flag::TRUNCATE_AS_WARNING));
+        let mut ctx = EvalContext::new(cfg.clone());
+        let dur = Duration::parse_with_ctx(&mut ctx, b"00:00:00.46", 1).unwrap();
+        assert_eq!("00:00:00.4", &format!("{}", dur));
+        assert_eq!(1, ctx.warnings.warning_cnt);
+
+        // truncation never carries, unlike rounding
+        let mut ctx = EvalContext::new(cfg.clone());
+        let dur = Duration::parse_with_ctx(&mut ctx, b"00:00:59.9999999", 6).unwrap();
+        assert_eq!("00:00:59.999999", &format!("{}", dur));
+        assert_eq!(1, ctx.warnings.warning_cnt);
+
+        // lossless input produces no warning, and a negative fraction
+        // truncated to zero drops the sign
+        let mut ctx = EvalContext::new(cfg.clone());
+        let dur = Duration::parse_with_ctx(&mut ctx, b"-00:00:00.4", 0).unwrap();
+        assert_eq!("00:00:00", &format!("{}", dur));
+        assert!(!dur.get_neg());
+        assert_eq!(1, ctx.warnings.warning_cnt);
+
+        let mut ctx = EvalContext::new(cfg);
+        let dur = Duration::parse_with_ctx(&mut ctx, b"11:30:45.5", 1).unwrap();
+        assert_eq!("11:30:45.5", &format!("{}", dur));
+        assert_eq!(0, ctx.warnings.warning_cnt);
+    }
+
+    #[test]
+    fn test_parse_best_effort() {
+        use crate::coprocessor::dag::expr::{EvalConfig, EvalContext, Flag};
+        use std::sync::Arc;
+
+        // clean input parses without a warning, like `parse`
+        let mut ctx = EvalContext::default();
+        let dur = Duration::parse_best_effort(&mut ctx, b"11:22:33", 0).unwrap();
+        assert_eq!("11:22:33", &format!("{}", dur));
+        assert_eq!(0, ctx.warnings.warning_cnt);
+
+        let cfg = Arc::new(EvalConfig::from_flag(Flag::TRUNCATE_AS_WARNING));
+        let cases: Vec<(&'static [u8], &'static str)> = vec![
+            (b"11:22:33xyz", "11:22:33.0"),
+            (b"11:22:33.1abc", "11:22:33.1"),
+            (b"11:22:", "11:22:00.0"),
+            (b"1x:22:33", "00:00:01.0"),
+        ];
+        for (input, expect) in cases {
+            let mut ctx = EvalContext::new(cfg.clone());
+            let dur = Duration::parse_best_effort(&mut ctx, input, 1).unwrap();
+            assert_eq!(expect, &format!("{}", dur));
+            assert_eq!(1, ctx.warnings.warning_cnt, "{:?}", input);
+
+            // strict mode reports the truncation as an error
+            let mut ctx = EvalContext::default();
+            assert!(Duration::parse_best_effort(&mut ctx, input, 1).is_err());
+        }
+
+        // nothing salvageable: the original parse error is kept
+        let mut ctx = EvalContext::new(cfg);
+        assert!(Duration::parse_best_effort(&mut ctx, b"xyz", 0).is_err());
+        assert_eq!(0, ctx.warnings.warning_cnt);
+    }
+
+    #[test]
+    fn test_saturating_with_warning() {
+        use crate::coprocessor::dag::expr::EvalContext;
+
+        // in-range values parse cleanly without a warning
+        let mut ctx = EvalContext::default();
+        let dur = Duration::parse_saturating(&mut ctx, b"838:59:59", 0).unwrap();
+        assert_eq!("838:59:59", &format!("{}", dur));
+        assert_eq!(0, ctx.warnings.warning_cnt);
+
+        // out-of-range values clamp to the boundary and warn
+        let mut ctx = EvalContext::default();
+        let dur = Duration::parse_saturating(&mut ctx, b"850:00:00", 0).unwrap();
+        assert_eq!("838:59:59", &format!("{}", dur));
+        assert_eq!(1, ctx.warnings.warning_cnt);
+
+        let mut ctx = EvalContext::default();
+        let dur = Duration::parse_saturating(&mut ctx, b"-39 10:00:00", 2).unwrap();
+        assert_eq!("-838:59:59.99", &format!("{}", dur));
+        assert_eq!(1, ctx.warnings.warning_cnt);
+
+        // malformed input is still an error
+        let mut ctx = EvalContext::default();
+        assert!(Duration::parse_saturating(&mut ctx, b"invalid", 0).is_err());
+
+        // arithmetic clamps and warns the same way
+        let mut ctx = EvalContext::default();
+        let lhs = Duration::parse(b"800:00:00", 0).unwrap();
+        let rhs = Duration::parse(b"100:00:00", 0).unwrap();
+        assert_eq!(
+            "838:59:59",
+            &format!("{}", lhs.add_saturating(&mut ctx, rhs))
+        );
+        assert_eq!(1, ctx.warnings.warning_cnt);
+
+        let mut ctx = EvalContext::default();
+        let neg_lhs = Duration::parse(b"-800:00:00", 0).unwrap();
+        assert_eq!(
+            "-838:59:59",
+            &format!("{}", neg_lhs.sub_saturating(&mut ctx, rhs))
+        );
+        assert_eq!(1, ctx.warnings.warning_cnt);
+    }
+
+    #[test]
+    fn test_sub_from_time() {
+        let cases = vec![
+            ("2018-01-02 00:30:00", "01:00:00", "2018-01-01 23:30:00"),
+            ("2018-03-01 00:30:00", "01:00:00", "2018-02-28 23:30:00"),
+            ("2019-01-01 00:00:00", "00:00:01", "2018-12-31 23:59:59"),
+            ("2018-01-01 23:30:00", "-01:00:00", "2018-01-02 00:30:00"),
+        ];
+
+        for (datetime, duration, exp) in cases {
+            let datetime = Time::parse_utc_datetime(datetime, 0).unwrap();
+            let duration = Duration::parse(duration.as_bytes(), 0).unwrap();
+            let exp = Time::parse_utc_datetime(exp, 0).unwrap();
+            assert_eq!(exp, duration.sub_from_time(datetime).unwrap());
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_parse_no_day_hour_overflow() {
+        // day and hour are both in range, the combination overflows
+        let err = Duration::parse_no_day_hour_overflow(b"34 23:00:00", 0).unwrap_err();
+        assert_eq!(format!("{}", err), "day 34 and hour 23 combined out of range");
+
+        // a day field that overflows on its own keeps the day error
+        let err = Duration::parse_no_day_hour_overflow(b"232 10", 0).unwrap_err();
+        assert_eq!(format!("{}", err), "day value 232 out of range");
+
+        // in-range values parse exactly like `parse`
+        let dur = Duration::parse_no_day_hour_overflow(b"1 10:11:12", 0).unwrap();
+        assert_eq!(dur, Duration::parse(b"1 10:11:12", 0).unwrap());
+    }
 
     #[test]
-    fn test_hours() {
-        let cases: Vec<(&str, i8, u32)> = vec![
-            ("31 11:30:45", 0, 31 * 24 + 11),
-            ("11:30:45", 0, 11),
-            ("-11:30:45.9233456", 0, 11),
-            ("272:59:59", 0, 272),
+    fn test_to_timecode() {
+        let cases = vec![
+            ("01:00:00.5", 24, "01:00:00:12"),
+            ("1 10:11:12", 24, "34:11:12:00"),
+            ("-00:00:01.25", 24, "-00:00:01:06"),
         ];
 
-        for (input, fsp, exp) in cases {
-            let dur = Duration::parse(input.as_bytes(), fsp).unwrap();
-            let res = dur.hours();
-            assert_eq!(exp, res);
+        for (input, fps, exp) in cases {
+            let dur = Duration::parse(input.as_bytes(), MAX_FSP).unwrap();
+            assert_eq!(exp, &dur.to_timecode(fps));
+        }
+
+        // Round trips are exact within one frame.
+        for fps in &[24, 30] {
+            for input in &["01:02:03.25", "12:34:56.789", "00:00:00.999999"] {
+                let dur = Duration::parse(input.as_bytes(), MAX_FSP).unwrap();
+                let round_tripped =
+                    Duration::from_timecode(dur.to_timecode(*fps).as_bytes(), *fps, MAX_FSP)
+                        .unwrap();
+                let frame = Duration::from_micros(MICROS_PER_SEC / i64::from(*fps), MAX_FSP)
+                    .unwrap();
+                let diff = dur
+                    .checked_sub(round_tripped)
+                    .unwrap()
+                    .abs();
+                assert!(diff <= frame, "{} != {} at {} fps", dur, round_tripped, fps);
+            }
         }
     }
 
     #[test]
-    fn test_minutes() {
-        let cases: Vec<(&str, i8, u32)> = vec![
-            ("31 11:30:45", 0, 30),
-            ("11:30:45", 0, 30),
-            ("-11:30:45.9233456", 0, 30),
+    fn test_duration_parser() {
+        let inputs: Vec<&'static [u8]> = vec![
+            b"10:11:12",
+            b"101112",
+            b"1 10:11:12.123456",
+            b"-11:30:45.9233456",
+            b"- 1 ",
+            b"2011-11-11",
+            b"--23",
+            b"",
         ];
 
-        for (input, fsp, exp) in cases {
-            let dur = Duration::parse(input.as_bytes(), fsp).unwrap();
-            let res = dur.minutes();
-            assert_eq!(exp, res);
+        let mut parser = DurationParser::new(6).unwrap();
+        for input in inputs {
+            assert_eq!(
+                parser.parse(input).ok(),
+                Duration::parse(input, 6).ok(),
+                "parser result mismatch for {:?}",
+                input
+            );
         }
+
+        assert!(DurationParser::new(7).is_err());
     }
 
     #[test]
-    fn test_secs() {
-        let cases: Vec<(&str, i8, u32)> = vec![
-            ("31 11:30:45", 0, 45),
-            ("11:30:45", 0, 45),
-            ("-11:30:45.9233456", 1, 45),
-            ("-11:30:45.9233456", 0, 46),
+    fn test_default() {
+        assert_eq!(Duration::default(), Duration::zero());
+        assert_eq!(0, Duration::default().fsp());
+    }
+
+    #[test]
+    fn test_fract_and_trunc() {
+        let cases = vec![
+            ("11:30:45.123456", 6, "00:00:00.123456", "11:30:45.000000"),
+            ("-11:30:45.123456", 6, "-00:00:00.123456", "-11:30:45.000000"),
+            ("11:30:45", 0, "00:00:00", "11:30:45"),
+            ("-00:00:00.5", 1, "-00:00:00.5", "00:00:00.0"),
         ];
 
-        for (input, fsp, exp) in cases {
+        for (input, fsp, fract, trunc) in cases {
             let dur = Duration::parse(input.as_bytes(), fsp).unwrap();
-            let res = dur.secs();
-            assert_eq!(exp, res);
+            assert_eq!(fract, &format!("{}", dur.fract()));
+            assert_eq!(trunc, &format!("{}", dur.trunc()));
+            assert_eq!(dur, dur.fract().checked_add(dur.trunc()).unwrap());
         }
     }
 
     #[test]
-    fn test_micros() {
-        let cases: Vec<(&str, i8, u32)> = vec![
-            ("31 11:30:45.123", 6, 123000),
-            ("11:30:45.123345", 3, 123000),
-            ("11:30:45.123345", 5, 123350),
-            ("11:30:45.123345", 6, 123345),
-            ("11:30:45.1233456", 6, 123346),
-            ("11:30:45.9233456", 0, 0),
-            ("11:30:45.000010", 6, 10),
-            ("11:30:45.00010", 5, 100),
+    fn test_combine_with_date() {
+        let cases = vec![
+            ("2018-01-01 23:30:00", "01:00:00", "2018-01-02 00:30:00"),
+            ("2018-01-02 00:30:00", "-01:00:00", "2018-01-01 23:30:00"),
+            ("2018-02-28 23:00:00", "25:00:00", "2018-03-02 00:00:00"),
         ];
 
-        for (input, fsp, exp) in cases {
-            let dur = Duration::parse(input.as_bytes(), fsp).unwrap();
-            let res = dur.micros();
-            assert_eq!(exp, res);
+        for (date, duration, exp) in cases {
+            let date = Time::parse_utc_datetime(date, 0).unwrap();
+            let duration = Duration::parse(duration.as_bytes(), 0).unwrap();
+            let exp = Time::parse_utc_datetime(exp, 0).unwrap();
+            assert_eq!(exp, duration.combine_with_date(date).unwrap());
         }
+
+        let date = Time::parse_utc_datetime("9999-12-31 23:00:00", 0).unwrap();
+        let duration = Duration::parse(b"838:59:59", 0).unwrap();
+        assert!(duration.combine_with_date(date).is_err());
     }
 
     #[test]
-    fn test_parse() {
-        let cases: Vec<(&'static [u8], i8, Option<&'static str>)> = vec![
-            (b"10:11:12", 0, Some("10:11:12")),
-            (b"101112", 0, Some("10:11:12")),
-            (b"10:11", 0, Some("10:11:00")),
-            (b"101112.123456", 0, Some("10:11:12")),
-            (b"1112", 0, Some("00:11:12")),
-            (b"12", 0, Some("00:00:12")),
-            (b"1 12", 0, Some("36:00:00")),
-            (b"1 10:11:12", 0, Some("34:11:12")),
-            (b"1 10:11:12.123456", 0, Some("34:11:12")),
-            (b"1 10:11:12.123456", 4, Some("34:11:12.1235")),
-            (b"1 10:11:12.12", 4, Some("34:11:12.1200")),
-            (b"1 10:11:12.1234565", 6, Some("34:11:12.123457")),
-            (b"1 10:11:12.9999995", 6, Some("34:11:13.000000")),
-            (b"1 10:11:12.123456", 7, None),
-            (b"10:11:12.123456", 0, Some("10:11:12")),
-            (b"1 10:11", 0, Some("34:11:00")),
-            (b"1 10", 0, Some("34:00:00")),
-            (b"24 10", 0, Some("586:00:00")),
-            (b"-24 10", 0, Some("-586:00:00")),
-            (b"0 10", 0, Some("10:00:00")),
-            (b"-10:10:10", 0, Some("-10:10:10")),
-            (b"-838:59:59", 0, Some("-838:59:59")),
-            (b"838:59:59", 0, Some("838:59:59")),
-            (b"23:60:59", 0, None),
-            (b"54:59:59", 0, Some("54:59:59")),
-            (b"2011-11-11 00:00:01", 0, None),
-            (b"2011-11-11", 0, None),
-            (b"--23", 0, None),
-            (b"232 10", 0, None),
-            (b"-232 10", 0, None),
-            (b"00:00:00.1", 0, Some("00:00:00")),
-            (b"00:00:00.1", 1, Some("00:00:00.1")),
-            (b"00:00:00.777777", 2, Some("00:00:00.78")),
-            (b"00:00:00.777777", 6, Some("00:00:00.777777")),
-            (b"00:00:00.001", 3, Some("00:00:00.001")),
-            // NOTE: The following case is easy to fail.
-            (b"- 1 ", 0, Some("-00:00:01")),
-            (b"1:2:3", 0, Some("01:02:03")),
-            (b"1 1:2:3", 0, Some("25:02:03")),
-            (b"-1 1:2:3.123", 3, Some("-25:02:03.123")),
-            (b"-.123", 3, Some("-00:00:00.123")),
-            (b"12345", 0, Some("01:23:45")),
-            (b"-123", 0, Some("-00:01:23")),
-            (b"-23", 0, Some("-00:00:23")),
-            (b"- 1 1", 0, Some("-25:00:00")),
-            (b"-1 1", 0, Some("-25:00:00")),
-            (b" - 1:2:3 .123 ", 3, Some("-01:02:03.123")),
-            (b" - 1 :2 :3 .123 ", 3, Some("-01:02:03.123")),
-            (b" - 1 : 2 :3 .123 ", 3, Some("-01:02:03.123")),
-            (b" - 1 : 2 :  3 .123 ", 3, Some("-01:02:03.123")),
-            (b" - 1 .123 ", 3, Some("-00:00:01.123")),
-            (b"-", 0, None),
-            (b"", 0, None),
-            (b"18446744073709551615:59:59", 0, None),
-            (b"1::2:3", 0, None),
-            (b"1.23 3", 0, None),
-        ];
-
-        for (input, fsp, expect) in cases {
-            let got = Duration::parse(input, fsp);
+    fn test_to_datetime() {
+        use crate::coprocessor::dag::expr::EvalContext;
 
-            if let Some(expect) = expect {
-                assert_eq!(
-                    expect,
-                    &format!(
-                        "{}",
-                        got.unwrap_or_else(|_| panic!(str::from_utf8(input).unwrap().to_string()))
-                    )
-                );
-            } else {
-                assert!(
-                    got.is_err(),
-                    format!(
-                        "{} should not be passed, got {:?}",
-                        str::from_utf8(input).unwrap(),
-                        got
-                    )
-                );
-            }
+        // The anchoring date is the current day, so only the clock part is
+        // deterministic: converting back must recover the original duration.
+        let cases = vec![("11:30:45.123456", 6), ("00:00:00", 0), ("23:59:59.9", 1)];
+        let mut ctx = EvalContext::default();
+        for (input, fsp) in cases {
+            let dur = Duration::parse(input.as_bytes(), fsp).unwrap();
+            let t = dur.to_datetime(&mut ctx).unwrap();
+            assert_eq!(dur, t.to_duration().unwrap());
         }
     }
 
     #[test]
-    fn test_to_decimal() {
-        let cases = vec![
-            ("31 11:30:45", 0, "7553045"),
-            ("31 11:30:45", 6, "7553045.000000"),
-            ("31 11:30:45", 0, "7553045"),
-            ("31 11:30:45.123", 6, "7553045.123000"),
-            ("11:30:45", 0, "113045"),
-            ("11:30:45", 6, "113045.000000"),
-            ("11:30:45.123", 6, "113045.123000"),
-            ("11:30:45.123345", 0, "113045"),
-            ("11:30:45.123345", 3, "113045.123"),
-            ("11:30:45.123345", 5, "113045.12335"),
-            ("11:30:45.123345", 6, "113045.123345"),
-            ("11:30:45.1233456", 6, "113045.123346"),
-            ("11:30:45.9233456", 0, "113046"),
-            ("-11:30:45.9233456", 0, "-113046"),
+    fn test_parse_numeric() {
+        let cases: Vec<(i64, i8, Option<&'static str>)> = vec![
+            (123_456, 0, Some("12:34:56")),
+            (-1122, 0, Some("-00:11:22")),
+            (8_385_959, 0, Some("838:59:59")),
+            (1, 3, Some("00:00:01.000")),
+            (0, 0, Some("00:00:00")),
+            (126_099, 0, None),
+            (120_060, 0, None),
+            (8_395_959, 0, None),
         ];
 
-        for (input, fsp, exp) in cases {
-            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
-            let res = format!("{}", Decimal::try_from(t).unwrap());
-            assert_eq!(exp, res);
+        for (input, fsp, expect) in cases {
+            let got = Duration::parse_numeric(input, fsp);
+            match expect {
+                Some(expect) => assert_eq!(expect, &format!("{}", got.unwrap())),
+                None => assert!(got.is_err(), "{} should not be passed", input),
+            }
         }
+
+        let dec: Decimal = "113045.5".parse().unwrap();
+        let dur = Duration::parse_numeric_decimal(&dec, 1).unwrap();
+        assert_eq!("11:30:45.5", &format!("{}", dur));
+
+        let dec: Decimal = "116045".parse().unwrap();
+        assert!(Duration::parse_numeric_decimal(&dec, 0).is_err());
     }
 
     #[test]
-    fn test_round_frac() {
-        let cases = vec![
-            ("11:30:45.123456", 4, "11:30:45.1235"),
-            ("11:30:45.123456", 6, "11:30:45.123456"),
-            ("11:30:45.123456", 0, "11:30:45"),
-            ("11:59:59.999999", 3, "12:00:00.000"),
-            ("1 11:30:45.123456", 1, "35:30:45.1"),
-            ("1 11:30:45.999999", 4, "35:30:46.0000"),
-            ("-1 11:30:45.999999", 0, "-35:30:46"),
-            ("-1 11:59:59.9999", 2, "-36:00:00.00"),
+    fn test_from_i64() {
+        use crate::coprocessor::dag::expr::{EvalConfig, EvalContext, Flag};
+        use std::sync::Arc;
+
+        let cases: Vec<(i64, i8, &'static str)> = vec![
+            (101_112, 0, "10:11:12"),
+            (-101_112, 0, "-10:11:12"),
+            (8_385_959, 0, "838:59:59"),
+            (-8_385_959, 0, "-838:59:59"),
+            (1, 3, "00:00:01.000"),
+            (0, 2, "00:00:00.00"),
         ];
-        for (input, fsp, exp) in cases {
-            let t = Duration::parse(input.as_bytes(), MAX_FSP)
-                .unwrap()
-                .round_frac(fsp)
-                .unwrap();
-            let res = format!("{}", t);
-            assert_eq!(exp, res);
+
+        for (input, fsp, expect) in cases {
+            let mut ctx = EvalContext::default();
+            let got = Duration::from_i64(&mut ctx, input, fsp).unwrap();
+            assert_eq!(expect, &format!("{}", got));
+            assert_eq!(ctx.warnings.warning_cnt, 0);
         }
+
+        // beyond `838:59:59` is an overflow error, left to the caller
+        let mut ctx = EvalContext::default();
+        let err = Duration::from_i64(&mut ctx, 8_500_000, 0).unwrap_err();
+        assert!(err.is_overflow());
+
+        // in range but not a valid `[H]HHMMSS` value: an error in strict
+        // mode, zero plus a warning in non-strict mode
+        let mut ctx = EvalContext::default();
+        assert!(Duration::from_i64(&mut ctx, 126_099, 0).is_err());
+
+        let cfg = Arc::new(EvalConfig::from_flag(Flag::TRUNCATE_AS_WARNING));
+        let mut ctx = EvalContext::new(cfg);
+        let got = Duration::from_i64(&mut ctx, 126_099, 0).unwrap();
+        assert_eq!("00:00:00", &format!("{}", got));
+        assert_eq!(ctx.warnings.warning_cnt, 1);
     }
 
     #[test]
-    fn test_codec() {
-        let cases = vec![
-            ("11:30:45.123456", 4),
-            ("11:30:45.123456", 6),
-            ("11:30:45.123456", 0),
-            ("11:59:59.999999", 3),
-            ("1 11:30:45.123456", 1),
-            ("1 11:30:45.999999", 4),
-            ("-1 11:30:45.999999", 0),
-            ("-1 11:59:59.9999", 2),
+    fn test_parse_day_out_of_range() {
+        let cases: Vec<(&'static [u8], u32)> = vec![
+            (b"232 10", 232),
+            (b"-232 10", 232),
+            (b"35 10:00:00", 35),
         ];
-        for (input, fsp) in cases {
-            let t = Duration::parse(input.as_bytes(), fsp).unwrap();
-            let mut buf = vec![];
-            buf.encode_duration(t).unwrap();
-            let got = Duration::decode(&mut buf.as_slice()).unwrap();
-            assert_eq!(t, got);
+
+        for (input, day) in cases {
+            let err = Duration::parse(input, 0).unwrap_err();
+            assert_eq!(
+                format!("{}", err),
+                format!("day value {} out of range", day)
+            );
         }
     }
 
     #[test]
-    fn test_checked_add_and_sub_duration() {
-        /// `MAX_TIME_IN_SECS` is the maximum for mysql time type.
-        const MAX_TIME_IN_SECS: i64 =
-            (MAX_HOURS * SECS_PER_HOUR + MAX_MINUTES * SECS_PER_MINUTE + MAX_SECONDS) as i64;
+    fn test_cmp_with_fsp() {
+        let lhs = Duration::parse(b"12:00:00", 0).unwrap();
+        let rhs = Duration::parse(b"12:00:00", 6).unwrap();
 
-        let cases = vec![
-            ("11:30:45.123456", "00:00:14.876545", "11:31:00.000001"),
-            ("11:30:45.123456", "00:30:00", "12:00:45.123456"),
-            ("11:30:45.123456", "12:30:00", "1 00:00:45.123456"),
-            ("11:30:45.123456", "1 12:30:00", "2 00:00:45.123456"),
-        ];
-        for (lhs, rhs, exp) in cases.clone() {
-            let lhs = Duration::parse(lhs.as_bytes(), 6).unwrap();
-            let rhs = Duration::parse(rhs.as_bytes(), 6).unwrap();
-            let res = lhs.checked_add(rhs).unwrap();
-            let exp = Duration::parse(exp.as_bytes(), 6).unwrap();
-            assert_eq!(res, exp);
-        }
-        for (exp, rhs, lhs) in cases {
-            let lhs = Duration::parse(lhs.as_bytes(), 6).unwrap();
-            let rhs = Duration::parse(rhs.as_bytes(), 6).unwrap();
-            let res = lhs.checked_sub(rhs).unwrap();
-            let exp = Duration::parse(exp.as_bytes(), 6).unwrap();
-            assert_eq!(res, exp);
-        }
+        assert_eq!(lhs.cmp(&rhs), Ordering::Equal);
+        assert_eq!(lhs.cmp_with_fsp(&lhs), Ordering::Equal);
+        assert_eq!(lhs.cmp_with_fsp(&rhs), Ordering::Less);
+        assert_eq!(rhs.cmp_with_fsp(&lhs), Ordering::Greater);
 
-        let lhs = Duration::parse(b"00:00:01", 6).unwrap();
-        let rhs = Duration::from_nanos(MAX_TIME_IN_SECS * NANOS_PER_SEC, 6).unwrap();
-        assert_eq!(lhs.checked_add(rhs), None);
-        let lhs = Duration::parse(b"-00:00:01", 6).unwrap();
-        let rhs = Duration::from_nanos(MAX_TIME_IN_SECS * NANOS_PER_SEC, 6).unwrap();
-        assert_eq!(lhs.checked_sub(rhs), None);
+        let smaller = Duration::parse(b"11:59:59", 6).unwrap();
+        assert_eq!(smaller.cmp_with_fsp(&lhs), Ordering::Less);
     }
 }
 
@@ -1040,6 +4678,36 @@ mod benches {
         })
     }
 
+    #[bench]
+    fn bench_total_hours(b: &mut test::Bencher) {
+        let duration = Duration::parse(b"-838:59:59.999999", 6).unwrap();
+        b.iter(|| {
+            let duration = test::black_box(duration);
+            let _ = test::black_box(duration.total_hours());
+        })
+    }
+
+    #[bench]
+    fn bench_to_decimal_via_string(b: &mut test::Bencher) {
+        // the old format-then-parse path, kept for comparison with
+        // `bench_to_decimal` now that `TryFrom` writes digits directly
+        let duration = Duration::parse(b"-12:34:56.123456", 6).unwrap();
+        b.iter(|| {
+            let duration = test::black_box(duration);
+            let dec: Decimal = duration.format("").parse().unwrap();
+            test::black_box(dec);
+        })
+    }
+
+    #[bench]
+    fn bench_to_decimal_exact(b: &mut test::Bencher) {
+        let duration = Duration::parse(b"-12:34:56.123456", 6).unwrap();
+        b.iter(|| {
+            let duration = test::black_box(duration);
+            let _ = test::black_box(duration.to_decimal_exact().unwrap());
+        })
+    }
+
     #[bench]
     fn bench_round_frac(b: &mut test::Bencher) {
         let (duration, fsp) = (Duration::parse(b"12:34:56.789", 3).unwrap(), 2);
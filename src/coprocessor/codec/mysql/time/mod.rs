@@ -795,6 +795,25 @@ impl Time {
             None
         }
     }
+
+    /// Computes `self - rhs` as a `MyDuration`, the primitive behind
+    /// `TIMEDIFF` with two datetime operands. The result fsp is the wider of
+    /// the two operands'; a difference beyond the TIME range saturates to
+    /// `±838:59:59` (plus the widest fraction at that fsp) the way MySQL
+    /// clamps it. A zero datetime has no well-defined difference and errors.
+    pub fn sub_datetime(&self, rhs: &Time) -> Result<MyDuration> {
+        if self.is_zero() || rhs.is_zero() {
+            return Err(box_err!("subtraction on a zero datetime is undefined"));
+        }
+
+        let fsp = self.fsp.max(rhs.fsp);
+        let diff = self.time.clone().signed_duration_since(rhs.time.clone());
+        match diff.num_nanoseconds() {
+            Some(nanos) => MyDuration::from_nanos(nanos, fsp as i8)
+                .or_else(|_| Ok(MyDuration::saturate(nanos < 0, fsp))),
+            None => Ok(MyDuration::saturate(diff.num_seconds() < 0, fsp)),
+        }
+    }
 }
 
 impl PartialOrd for Time {
@@ -1643,4 +1662,40 @@ mod tests {
         let rhs = MyDuration::parse(b"01:00:00", 6).unwrap();
         assert_eq!(lhs.checked_sub(rhs), None);
     }
+
+    #[test]
+    fn test_sub_datetime() {
+        let cases = vec![
+            (
+                "2018-12-30 11:30:45.123456",
+                "2018-12-30 11:30:00.123456",
+                "00:00:45.000000",
+            ),
+            (
+                "2018-12-30 11:30:00",
+                "2018-12-30 11:30:45",
+                "-00:00:45.000000",
+            ),
+            (
+                "2019-01-01 00:00:00.1",
+                "2018-12-31 23:59:59.9",
+                "00:00:00.200000",
+            ),
+            // Beyond the TIME range, saturate towards the sign of the
+            // difference the way MySQL clamps TIMEDIFF.
+            ("2019-03-01 00:00:00", "2018-12-30 00:00:00", "838:59:59.999999"),
+            ("2018-12-30 00:00:00", "2019-03-01 00:00:00", "-838:59:59.999999"),
+        ];
+        for (lhs, rhs, exp) in cases {
+            let lhs = Time::parse_utc_datetime(lhs, 6).unwrap();
+            let rhs = Time::parse_utc_datetime(rhs, 6).unwrap();
+            let res = lhs.sub_datetime(&rhs).unwrap();
+            assert_eq!(exp, &format!("{}", res));
+        }
+
+        let zero = Time::parse_utc_datetime("0000-00-00 00:00:00", 0).unwrap();
+        let t = Time::parse_utc_datetime("2018-12-30 11:30:45", 0).unwrap();
+        assert!(zero.sub_datetime(&t).is_err());
+        assert!(t.sub_datetime(&zero).is_err());
+    }
 }
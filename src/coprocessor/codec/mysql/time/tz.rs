@@ -38,6 +38,30 @@ impl Tz {
         }
     }
 
+    /// Constructs a time zone from a specifier the way `CONVERT_TZ`
+    /// arguments are written: either a numeric offset like `"+08:00"` /
+    /// `"-06:00"`, or a name resolved by `from_tz_name` (an IANA zone such
+    /// as `America/Los_Angeles`, or `system`). Offsets are bounded to
+    /// `-13:59 ~ +14:00` like MySQL.
+    pub fn from_spec(spec: &str) -> Option<Self> {
+        let first = *spec.as_bytes().first()?;
+        if first != b'+' && first != b'-' {
+            return Tz::from_tz_name(spec);
+        }
+        let mut parts = spec[1..].splitn(2, ':');
+        let hours: i64 = parts.next()?.parse().ok()?;
+        let minutes: i64 = parts.next()?.parse().ok()?;
+        if hours < 0 || minutes < 0 || minutes > 59 {
+            return None;
+        }
+        let secs = (hours * 60 + minutes) * 60;
+        let secs = if first == b'-' { -secs } else { secs };
+        if secs > 14 * 3600 || secs < -(13 * 3600 + 59 * 60) {
+            return None;
+        }
+        Tz::from_offset(secs)
+    }
+
     /// Constructs a UTC time zone.
     pub fn utc() -> Self {
         Tz::Name(chrono_tz::UTC)
@@ -193,4 +193,59 @@ mod tests {
 
         assert_eq!(aggr_result[0].as_real_slice(), &[Real::new(54.5).ok()]);
     }
+
+    /// SUM(Duration) should produce (Decimal).
+    #[test]
+    fn test_integration_duration_column() {
+        let expr = ExprDefBuilder::aggr_func(ExprType::Sum, FieldTypeTp::NewDecimal)
+            .push_child(ExprDefBuilder::column_ref(0, FieldTypeTp::Duration))
+            .build();
+        AggrFnDefinitionParserSum.check_supported(&expr).unwrap();
+
+        let src_schema = [FieldTypeTp::Duration.into()];
+        let mut columns = LazyBatchColumnVec::from(vec![{
+            let mut col = LazyBatchColumn::decoded_with_capacity_and_tp(0, EvalType::Duration);
+            col.mut_decoded()
+                .push_duration(Some(Duration::parse(b"1:00:00", 0).unwrap()));
+            col.mut_decoded().push_duration(None);
+            col.mut_decoded()
+                .push_duration(Some(Duration::parse(b"00:00:30", 0).unwrap()));
+            col.mut_decoded()
+                .push_duration(Some(Duration::parse(b"-00:00:10", 0).unwrap()));
+            col
+        }]);
+        let logical_rows = vec![0, 1, 2, 3];
+
+        let mut schema = vec![];
+        let mut exp = vec![];
+
+        let aggr_fn = AggrFnDefinitionParserSum
+            .parse(expr, &Tz::utc(), &src_schema, &mut schema, &mut exp)
+            .unwrap();
+        assert_eq!(schema.len(), 1);
+        assert_eq!(schema[0].tp(), FieldTypeTp::NewDecimal);
+
+        assert_eq!(exp.len(), 1);
+
+        let mut state = aggr_fn.create_state();
+        let mut ctx = EvalContext::default();
+
+        let exp_result = exp[0]
+            .eval(&mut ctx, &src_schema, &mut columns, &logical_rows, 4)
+            .unwrap();
+        let exp_result = exp_result.vector_value().unwrap();
+        let slice: &[Option<Decimal>] = exp_result.as_ref().as_ref();
+        state
+            .update_vector(&mut ctx, slice, exp_result.logical_rows())
+            .unwrap();
+
+        let mut aggr_result = [VectorValue::with_capacity(0, EvalType::Decimal)];
+        state.push_result(&mut ctx, &mut aggr_result).unwrap();
+
+        // `1:00:00 + 00:00:30 - 00:00:10` summed in the `HHMMSS` decimal form.
+        assert_eq!(
+            aggr_result[0].as_decimal_slice(),
+            &[Some(Decimal::from(10020))]
+        );
+    }
 }
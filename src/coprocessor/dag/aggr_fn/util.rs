@@ -43,6 +43,13 @@ pub fn rewrite_exp_for_sum_avg(schema: &[FieldType], exp: &mut RpnExpression) ->
             .tp(FieldTypeTp::NewDecimal)
             .flen(cop_datatype::MAX_DECIMAL_WIDTH)
             .build(),
+        // TIME columns are summed as `Decimal`, keeping the fractional
+        // second digits of the source as the fraction of the decimal.
+        EvalType::Duration => FieldTypeBuilder::new()
+            .tp(FieldTypeTp::NewDecimal)
+            .flen(cop_datatype::MAX_DECIMAL_WIDTH)
+            .decimal(ret_field_type.decimal())
+            .build(),
         _ => FieldTypeBuilder::new()
             .tp(FieldTypeTp::Double)
             .flen(cop_datatype::MAX_REAL_WIDTH)
@@ -435,9 +435,7 @@ impl ScalarFunc {
         row: &'a [Datum],
     ) -> Result<Option<Duration>> {
         let val = try_opt!(self.children[0].eval_int(ctx, row));
-        let s = format!("{}", val);
-        // TODO: port NumberToDuration from tidb.
-        match Duration::parse(s.as_bytes(), self.field_type.decimal() as i8) {
+        match Duration::from_i64(ctx, val, self.field_type.decimal() as i8) {
             Ok(dur) => Ok(Some(dur)),
             Err(e) => {
                 if e.is_overflow() {
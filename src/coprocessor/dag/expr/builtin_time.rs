@@ -5,11 +5,14 @@ use std::borrow::Cow;
 use chrono::offset::TimeZone;
 use chrono::Datelike;
 
+use cop_datatype::FieldTypeAccessor;
+
 use super::{EvalContext, Result, ScalarFunc};
 use crate::coprocessor::codec::error::Error;
+use crate::coprocessor::codec::mysql::duration::timediff_time;
 use crate::coprocessor::codec::mysql::time::extension::DateTimeExtension;
 use crate::coprocessor::codec::mysql::time::weekmode::WeekMode;
-use crate::coprocessor::codec::mysql::{Duration as MyDuration, Time, TimeType};
+use crate::coprocessor::codec::mysql::{Duration as MyDuration, Time, TimeType, Tz, MAX_FSP};
 use crate::coprocessor::codec::Datum;
 use crate::coprocessor::dag::expr::SqlMode;
 
@@ -34,6 +37,21 @@ impl ScalarFunc {
         Ok(Some(Cow::Owned(t.unwrap().into_bytes())))
     }
 
+    #[inline]
+    pub fn time_format<'a, 'b: 'a>(
+        &'b self,
+        ctx: &mut EvalContext,
+        row: &'a [Datum],
+    ) -> Result<Option<Cow<'a, [u8]>>> {
+        let dur = try_opt!(self.children[0].eval_duration(ctx, row));
+        let format_mask: Cow<'a, str> = try_opt!(self.children[1].eval_string_and_decode(ctx, row));
+        let t = dur.time_format(&format_mask);
+        if let Err(err) = t {
+            return ctx.handle_invalid_time_error(err).map(|_| None);
+        }
+        Ok(Some(Cow::Owned(t.unwrap().into_bytes())))
+    }
+
     #[inline]
     pub fn date<'a, 'b: 'a>(
         &'b self,
@@ -352,6 +370,36 @@ impl ScalarFunc {
         Ok(Some(days_diff))
     }
 
+    /// `CONVERT_TZ(dt, from_tz, to_tz)` reinterprets the wall-clock value in
+    /// `from_tz` and renders the same instant in `to_tz`. Both time zones
+    /// accept numeric offsets and IANA names; an unknown time zone, a zero
+    /// datetime or a wall-clock value that does not exist in `from_tz`
+    /// (a DST gap) yields NULL, matching MySQL.
+    pub fn convert_tz<'a, 'b: 'a>(
+        &'b self,
+        ctx: &mut EvalContext,
+        row: &'a [Datum],
+    ) -> Result<Option<Cow<'a, Time>>> {
+        let t: Cow<'a, Time> = try_opt!(self.children[0].eval_time(ctx, row));
+        if t.is_zero() {
+            return Ok(None);
+        }
+        let from: Cow<'_, str> = try_opt!(self.children[1].eval_string_and_decode(ctx, row));
+        let to: Cow<'_, str> = try_opt!(self.children[2].eval_string_and_decode(ctx, row));
+        let (from_tz, to_tz) = match (Tz::from_spec(&from), Tz::from_spec(&to)) {
+            (Some(from_tz), Some(to_tz)) => (from_tz, to_tz),
+            _ => return Ok(None),
+        };
+        let wall_clock = t.get_time().naive_local();
+        let instant = match from_tz.from_local_datetime(&wall_clock).earliest() {
+            Some(instant) => instant,
+            None => return Ok(None),
+        };
+        let converted = to_tz.from_utc_datetime(&instant.naive_utc());
+        let converted = Time::new(converted, TimeType::DateTime, t.get_fsp() as i8)?;
+        Ok(Some(Cow::Owned(converted)))
+    }
+
     #[inline]
     pub fn add_datetime_and_duration<'a, 'b: 'a>(
         &'b self,
@@ -540,6 +588,49 @@ impl ScalarFunc {
         Ok(None)
     }
 
+    #[inline]
+    pub fn make_time(&self, ctx: &mut EvalContext, row: &[Datum]) -> Result<Option<MyDuration>> {
+        let hour = try_opt!(self.children[0].eval_int(ctx, row));
+        let minute = try_opt!(self.children[1].eval_int(ctx, row));
+        let second = try_opt!(self.children[2].eval_real(ctx, row));
+        if minute < 0 || minute >= 60 || second < 0f64 || second >= 60f64 {
+            return Ok(None);
+        }
+
+        let (mut hour, mut minute, mut second) = (hour, minute, second);
+        if hour < -838 || hour > 838 {
+            ctx.warnings.append_warning(Error::truncated_wrong_val(
+                "TIME",
+                &format!("{}:{}:{}", hour, minute, second),
+            ));
+            hour = if hour < 0 { -838 } else { 838 };
+            minute = 59;
+            second = 59f64;
+        }
+
+        let fsp = self.field_type.decimal();
+        let fsp = if fsp == cop_datatype::UNSPECIFIED_LENGTH {
+            MAX_FSP
+        } else {
+            fsp as i8
+        };
+        let micros = (hour.abs() * 3600 + minute * 60) * 1_000_000
+            + (second * 1_000_000f64).round() as i64;
+        let micros = if hour < 0 { -micros } else { micros };
+        Ok(Some(MyDuration::from_micros(micros, fsp)?))
+    }
+
+    #[inline]
+    pub fn duration_duration_time_diff(
+        &self,
+        ctx: &mut EvalContext,
+        row: &[Datum],
+    ) -> Result<Option<MyDuration>> {
+        let arg0 = try_opt!(self.children[0].eval_duration(ctx, row));
+        let arg1 = try_opt!(self.children[1].eval_duration(ctx, row));
+        Ok(Some(timediff_time(arg0, arg1)))
+    }
+
     #[inline]
     pub fn add_time_string_null<'a>(
         &self,
@@ -712,6 +803,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_time_format() {
+        let cases = vec![
+            (
+                "23:12:34.123456",
+                "%H %k %h %I %l %i %p %r %T %s %f %%",
+                "23 23 11 11 11 12 PM 11:12:34 PM 23:12:34 34 123456 %",
+            ),
+            (
+                "150:02:28.5",
+                "%H %k %h %l %i %s %f %T",
+                "150 150 06 6 02 28 500000 150:02:28",
+            ),
+            (
+                "-01:02:03",
+                "%H:%i:%s",
+                "-01:02:03",
+            ),
+            (
+                "00:30:00",
+                "%h %p %r",
+                "12 AM 12:30:00 AM",
+            ),
+            (
+                "11:23:45",
+                "%Y-%m-%d %H:%i:%s",
+                "0000-00-00 11:23:45",
+            ),
+        ];
+        let mut ctx = EvalContext::default();
+        for (arg1, arg2, exp) in cases {
+            test_ok_case_two_arg(
+                &mut ctx,
+                ScalarFuncSig::TimeFormat,
+                Datum::Dur(Duration::parse(arg1.as_bytes(), 6).unwrap()),
+                Datum::Bytes(arg2.to_string().into_bytes()),
+                Datum::Bytes(exp.to_string().into_bytes()),
+            );
+        }
+        // a duration has no month to spell out
+        test_err_case_two_arg(
+            &mut ctx,
+            ScalarFuncSig::TimeFormat,
+            Datum::Dur(Duration::parse(b"11:23:45", 0).unwrap()),
+            Datum::Bytes(b"%M".to_vec()),
+        );
+        // test NULL case
+        test_err_case_two_arg(
+            &mut ctx,
+            ScalarFuncSig::TimeFormat,
+            Datum::Null,
+            Datum::Null,
+        );
+    }
+
     #[test]
     fn test_date() {
         let cases = vec![
@@ -1414,6 +1560,56 @@ mod tests {
         test_err_case_two_arg(&mut ctx, ScalarFuncSig::DateDiff, Datum::Null, Datum::Null);
     }
 
+    #[test]
+    fn test_convert_tz() {
+        let cases = vec![
+            ("2019-06-10 12:00:00", "+00:00", "+08:00", "2019-06-10 20:00:00"),
+            ("2019-06-10 12:00:00", "-06:00", "+00:00", "2019-06-10 18:00:00"),
+            (
+                "2019-06-10 12:00:00",
+                "UTC",
+                "America/Los_Angeles",
+                "2019-06-10 05:00:00",
+            ),
+            // across the date line, in winter time
+            (
+                "2019-01-10 12:00:00",
+                "Asia/Shanghai",
+                "America/Los_Angeles",
+                "2019-01-09 20:00:00",
+            ),
+        ];
+        let mut ctx = EvalContext::default();
+        for (arg, from, to, exp) in cases {
+            let children = &[
+                datum_expr(Datum::Time(Time::parse_utc_datetime(arg, 0).unwrap())),
+                datum_expr(Datum::Bytes(from.as_bytes().to_vec())),
+                datum_expr(Datum::Bytes(to.as_bytes().to_vec())),
+            ];
+            let got = expr_build(&mut ctx, ScalarFuncSig::ConvertTz, children).unwrap();
+            match got {
+                Datum::Time(t) => assert_eq!(exp, &format!("{}", t), "{} {} {}", arg, from, to),
+                got => panic!("eval {} {} {} got {:?}", arg, from, to, got),
+            }
+        }
+
+        // an unknown time zone or a wall clock inside a DST gap gives NULL
+        let cases = vec![
+            ("2019-06-10 12:00:00", "No/Zone", "+08:00"),
+            ("2019-06-10 12:00:00", "+00:00", "+15:00"),
+            ("2019-03-10 02:30:00", "America/Los_Angeles", "+00:00"),
+        ];
+        for (arg, from, to) in cases {
+            let children = &[
+                datum_expr(Datum::Time(Time::parse_utc_datetime(arg, 0).unwrap())),
+                datum_expr(Datum::Bytes(from.as_bytes().to_vec())),
+                datum_expr(Datum::Bytes(to.as_bytes().to_vec())),
+            ];
+            let got = expr_build(&mut ctx, ScalarFuncSig::ConvertTz, children).unwrap();
+            assert_eq!(got, Datum::Null, "{} {} {}", arg, from, to);
+        }
+    }
+
     #[test]
     fn test_add_sub_datetime_and_duration() {
         let cases = vec![
@@ -1753,4 +1949,71 @@ mod tests {
         let mut ctx = EvalContext::default();
         test_ok_case_zero_arg(&mut ctx, ScalarFuncSig::AddTimeStringNull, Datum::Null);
     }
+
+    #[test]
+    fn test_make_time() {
+        let cases: Vec<(i64, i64, f64, i32, &'static str)> = vec![
+            (12, 15, 30.0, 0, "12:15:30"),
+            (25, 3, 5.55, 2, "25:03:05.55"),
+            (-25, 3, 5.0, 0, "-25:03:05"),
+            (0, 0, 0.0, 0, "00:00:00"),
+            // out-of-range hours clamp at the TIME boundary
+            (900, 0, 0.0, 0, "838:59:59"),
+            (-900, 0, 0.0, 0, "-838:59:59"),
+        ];
+        let mut ctx = EvalContext::default();
+        for (hour, minute, second, fsp, exp) in cases {
+            let children = &[
+                datum_expr(Datum::I64(hour)),
+                datum_expr(Datum::I64(minute)),
+                datum_expr(Datum::F64(second)),
+            ];
+            let mut f = scalar_func_expr(ScalarFuncSig::MakeTime, children);
+            f.mut_field_type().set_decimal(fsp);
+            let op = Expression::build(&mut ctx, f).unwrap();
+            let got = op.eval(&mut ctx, &[]).unwrap();
+            let exp = Datum::Dur(Duration::parse(exp.as_bytes(), fsp as i8).unwrap());
+            assert_eq!(got, exp);
+        }
+
+        // out-of-range minutes or seconds give NULL
+        let cases: Vec<(i64, f64)> = vec![(60, 1.0), (-1, 1.0), (1, 60.0), (1, -1.0)];
+        for (minute, second) in cases {
+            let children = &[
+                datum_expr(Datum::I64(1)),
+                datum_expr(Datum::I64(minute)),
+                datum_expr(Datum::F64(second)),
+            ];
+            let f = scalar_func_expr(ScalarFuncSig::MakeTime, children);
+            let op = Expression::build(&mut ctx, f).unwrap();
+            assert_eq!(op.eval(&mut ctx, &[]).unwrap(), Datum::Null);
+        }
+    }
+
+    #[test]
+    fn test_duration_duration_time_diff() {
+        let cases = vec![
+            ("11:30:45.5", 1, "00:30:45", 0, "11:00:00.5"),
+            ("00:30:45", 0, "11:30:45.5", 1, "-11:00:00.5"),
+            // saturates instead of erroring past the TIME boundary
+            ("-838:00:00", 0, "100:00:00", 0, "-838:59:59"),
+        ];
+        let mut ctx = EvalContext::default();
+        for (arg0, fsp0, arg1, fsp1, exp) in cases {
+            test_ok_case_two_arg(
+                &mut ctx,
+                ScalarFuncSig::DurationDurationTimeDiff,
+                Datum::Dur(Duration::parse(arg0.as_bytes(), fsp0).unwrap()),
+                Datum::Dur(Duration::parse(arg1.as_bytes(), fsp1).unwrap()),
+                Datum::Dur(Duration::parse(exp.as_bytes(), fsp0.max(fsp1)).unwrap()),
+            );
+        }
+        // test NULL case
+        test_err_case_two_arg(
+            &mut ctx,
+            ScalarFuncSig::DurationDurationTimeDiff,
+            Datum::Null,
+            Datum::Null,
+        );
+    }
 }
@@ -104,8 +104,12 @@ impl EvalConfig {
         self
     }
 
+    /// Sets the time zone from a name carried in the DAG request. An IANA
+    /// name (e.g. `Asia/Shanghai`) keeps the tz database rules so DST
+    /// transitions are respected; `system` and numeric specifiers like
+    /// `"+08:00"` are accepted as well.
     pub fn set_time_zone_by_name(&mut self, tz_name: &str) -> Result<&mut Self> {
-        match Tz::from_tz_name(tz_name) {
+        match Tz::from_spec(tz_name) {
             Some(tz) => {
                 self.tz = tz;
                 Ok(self)
@@ -344,6 +348,35 @@ mod tests {
         assert_eq!(warnings.warnings.len(), eval_cfg.max_warning_cnt);
     }
 
+    #[test]
+    fn test_set_time_zone_by_name() {
+        use crate::coprocessor::codec::mysql::Time;
+
+        // an IANA name keeps tz database rules: the same wall clock maps to
+        // different instants on either side of a DST transition
+        let mut cfg = EvalConfig::new();
+        cfg.set_time_zone_by_name("America/Los_Angeles").unwrap();
+        let before = Time::parse_datetime("2019-03-09 12:00:00", 0, &cfg.tz).unwrap();
+        let after = Time::parse_datetime("2019-03-10 12:00:00", 0, &cfg.tz).unwrap();
+        let elapsed = after
+            .get_time()
+            .signed_duration_since(before.get_time())
+            .num_hours();
+        assert_eq!(elapsed, 23);
+
+        // numeric specifiers are accepted like the offset field
+        let mut cfg = EvalConfig::new();
+        cfg.set_time_zone_by_name("+08:00").unwrap();
+        let mut by_offset = EvalConfig::new();
+        by_offset.set_time_zone_by_offset(8 * 3600).unwrap();
+        let t = Time::parse_datetime("2019-06-10 12:00:00", 0, &cfg.tz).unwrap();
+        let t_by_offset = Time::parse_datetime("2019-06-10 12:00:00", 0, &by_offset.tz).unwrap();
+        assert_eq!(t.get_time().timestamp(), t_by_offset.get_time().timestamp());
+
+        let mut cfg = EvalConfig::new();
+        assert!(cfg.set_time_zone_by_name("No/Zone").is_err());
+    }
+
     #[test]
     fn test_handle_division_by_zero() {
         let cases = vec![
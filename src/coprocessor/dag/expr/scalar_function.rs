@@ -109,6 +109,7 @@ impl ScalarFunc {
             | ScalarFuncSig::RoundWithFracInt
             | ScalarFuncSig::RoundWithFracReal
             | ScalarFuncSig::DateFormatSig
+            | ScalarFuncSig::TimeFormat
             | ScalarFuncSig::SHA2
             | ScalarFuncSig::TruncateInt
             | ScalarFuncSig::WeekWithMode
@@ -119,6 +120,7 @@ impl ScalarFunc {
             | ScalarFuncSig::Substring2Args
             | ScalarFuncSig::SubstringBinary2Args
             | ScalarFuncSig::DateDiff
+            | ScalarFuncSig::DurationDurationTimeDiff
             | ScalarFuncSig::AddDatetimeAndDuration
             | ScalarFuncSig::AddDatetimeAndString
             | ScalarFuncSig::AddDurationAndDuration
@@ -309,6 +311,8 @@ impl ScalarFunc {
             | ScalarFuncSig::RpadBinary
             | ScalarFuncSig::Locate3Args
             | ScalarFuncSig::LocateBinary3Args
+            | ScalarFuncSig::MakeTime
+            | ScalarFuncSig::ConvertTz
             | ScalarFuncSig::Replace => (3, 3),
 
             ScalarFuncSig::JsonArraySig
@@ -393,14 +397,12 @@ impl ScalarFunc {
             | ScalarFuncSig::Char
             | ScalarFuncSig::ConnectionID
             | ScalarFuncSig::Convert
-            | ScalarFuncSig::ConvertTz
             | ScalarFuncSig::CurrentDate
             | ScalarFuncSig::CurrentTime0Arg
             | ScalarFuncSig::CurrentTime1Arg
             | ScalarFuncSig::CurrentUser
             | ScalarFuncSig::Database
             | ScalarFuncSig::DateLiteral
-            | ScalarFuncSig::DurationDurationTimeDiff
             | ScalarFuncSig::DurationStringTimeDiff
             | ScalarFuncSig::ExportSet3Arg
             | ScalarFuncSig::ExportSet4Arg
@@ -424,7 +426,6 @@ impl ScalarFunc {
             | ScalarFuncSig::Lock
             | ScalarFuncSig::MakeDate
             | ScalarFuncSig::MakeSet
-            | ScalarFuncSig::MakeTime
             | ScalarFuncSig::NowWithArg
             | ScalarFuncSig::NowWithoutArg
             | ScalarFuncSig::NullTimeDiff
@@ -464,7 +465,6 @@ impl ScalarFunc {
             | ScalarFuncSig::SysDateWithoutFsp
             | ScalarFuncSig::TiDBVersion
             | ScalarFuncSig::Time
-            | ScalarFuncSig::TimeFormat
             | ScalarFuncSig::TimeLiteral
             | ScalarFuncSig::Timestamp1Arg
             | ScalarFuncSig::Timestamp2Args
@@ -952,6 +952,7 @@ dispatch_call! {
         Upper => upper,
         Lower => lower,
         DateFormatSig => date_format,
+        TimeFormat => time_format,
         MonthName => month_name,
         DayName => day_name,
         Bin => bin,
@@ -1008,6 +1009,7 @@ dispatch_call! {
 
         Date => date,
         LastDay => last_day,
+        ConvertTz => convert_tz,
         AddDatetimeAndDuration => add_datetime_and_duration,
         AddDatetimeAndString => add_datetime_and_string,
         AddTimeDateTimeNull => add_time_datetime_null,
@@ -1043,6 +1045,8 @@ dispatch_call! {
         AddTimeDurationNull => add_time_duration_null,
 
         SubDurationAndDuration => sub_duration_and_duration,
+        DurationDurationTimeDiff => duration_duration_time_diff,
+        MakeTime => make_time,
         SubDurationAndString => sub_duration_and_string,
         SubTimeDurationNull => sub_time_duration_null,
     }
@@ -1502,14 +1506,12 @@ mod tests {
             ScalarFuncSig::Char,
             ScalarFuncSig::ConnectionID,
             ScalarFuncSig::Convert,
-            ScalarFuncSig::ConvertTz,
             ScalarFuncSig::CurrentDate,
             ScalarFuncSig::CurrentTime0Arg,
             ScalarFuncSig::CurrentTime1Arg,
             ScalarFuncSig::CurrentUser,
             ScalarFuncSig::Database,
             ScalarFuncSig::DateLiteral,
-            ScalarFuncSig::DurationDurationTimeDiff,
             ScalarFuncSig::DurationStringTimeDiff,
             ScalarFuncSig::ExportSet3Arg,
             ScalarFuncSig::ExportSet4Arg,
@@ -1533,7 +1535,6 @@ mod tests {
             ScalarFuncSig::Lock,
             ScalarFuncSig::MakeDate,
             ScalarFuncSig::MakeSet,
-            ScalarFuncSig::MakeTime,
             ScalarFuncSig::NowWithArg,
             ScalarFuncSig::NowWithoutArg,
             ScalarFuncSig::NullTimeDiff,
@@ -1573,7 +1574,6 @@ mod tests {
             ScalarFuncSig::SysDateWithoutFsp,
             ScalarFuncSig::TiDBVersion,
             ScalarFuncSig::Time,
-            ScalarFuncSig::TimeFormat,
             ScalarFuncSig::TimeLiteral,
             ScalarFuncSig::Timestamp1Arg,
             ScalarFuncSig::Timestamp2Args,
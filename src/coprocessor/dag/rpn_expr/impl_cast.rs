@@ -37,6 +37,7 @@ pub fn get_cast_fn_rpn_node(
                 cast_uint_as_decimal_fn_meta()
             }
         }
+        (EvalType::Duration, EvalType::Decimal) => cast_duration_as_decimal_fn_meta(),
         (EvalType::Bytes, EvalType::Real) => cast_string_as_real_fn_meta(),
         (EvalType::DateTime, EvalType::Real) => cast_time_as_real_fn_meta(),
         (EvalType::Duration, EvalType::Real) => cast_duration_as_real_fn_meta(),
@@ -110,6 +111,27 @@ pub fn cast_int_as_decimal(
     }
 }
 
+/// The implementation for push down signature `CastDurationAsDecimal`.
+#[rpn_fn(capture = [ctx, extra])]
+#[inline]
+fn cast_duration_as_decimal(
+    ctx: &mut EvalContext,
+    extra: &RpnFnCallExtra<'_>,
+    val: &Option<Duration>,
+) -> Result<Option<Decimal>> {
+    match val {
+        None => Ok(None),
+        Some(val) => {
+            let dec = Decimal::try_from(*val)?;
+            Ok(Some(produce_dec_with_specified_tp(
+                ctx,
+                dec,
+                extra.ret_field_type,
+            )?))
+        }
+    }
+}
+
 /// The implementation for push down signature `CastStringAsReal`.
 #[rpn_fn(capture = [ctx])]
 #[inline]
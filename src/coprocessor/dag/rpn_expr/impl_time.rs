@@ -0,0 +1,183 @@
+// Copyright 2019 TiKV Project Authors. Licensed under Apache-2.0.
+
+use cop_codegen::rpn_fn;
+
+use crate::coprocessor::codec::data_type::*;
+use crate::coprocessor::codec::mysql::MAX_FSP;
+use crate::coprocessor::codec::Error;
+use crate::coprocessor::dag::expr::EvalContext;
+use crate::coprocessor::Result;
+
+/// Converts a seconds value (possibly fractional) into a `Duration`,
+/// implementing `SEC_TO_TIME`. The result fsp follows the fraction width of
+/// the argument; values beyond the TIME range are clamped to the
+/// `838:59:59` boundary with a truncation warning, the way MySQL handles
+/// the overflow in non-strict mode.
+#[rpn_fn(capture = [ctx])]
+#[inline]
+pub fn sec_to_time(ctx: &mut EvalContext, arg: &Option<Decimal>) -> Result<Option<Duration>> {
+    let dec = match arg {
+        None => return Ok(None),
+        Some(dec) => dec,
+    };
+    let fsp = dec.frac_cnt().min(MAX_FSP as u8);
+    let secs = dec.as_f64()?;
+    let micros = (secs * 1_000_000.0).round();
+    if micros >= i64::min_value() as f64 && micros <= i64::max_value() as f64 {
+        if let Ok(dur) = Duration::from_micros(micros as i64, fsp as i8) {
+            return Ok(Some(dur));
+        }
+    }
+    ctx.warnings
+        .append_warning(Error::truncated_wrong_val("TIME", &dec.to_string()));
+    Ok(Some(Duration::saturate(secs < 0.0, fsp)))
+}
+
+/// Implements `EXTRACT(unit FROM time)` for `Duration` operands. Only the
+/// hour-and-below units are meaningful for a TIME value; compound units
+/// concatenate their fields positionally the way MySQL does (e.g.
+/// `HOUR_MINUTE` of `10:20:30` is `1020`). Negative durations yield
+/// negative numbers.
+#[rpn_fn]
+#[inline]
+pub fn extract_duration(unit: &Option<Bytes>, arg: &Option<Duration>) -> Result<Option<Int>> {
+    let (unit, dur) = match (unit, arg) {
+        (Some(unit), Some(dur)) => (unit, *dur),
+        _ => return Ok(None),
+    };
+    let hours = i64::from(dur.hours());
+    let minutes = i64::from(dur.minutes());
+    let secs = i64::from(dur.secs());
+    let micros = i64::from(dur.subsec_micros());
+    let val = match String::from_utf8_lossy(unit).to_uppercase().as_str() {
+        "MICROSECOND" => micros,
+        "SECOND" => secs,
+        "MINUTE" => minutes,
+        "HOUR" => hours,
+        "SECOND_MICROSECOND" => secs * 1_000_000 + micros,
+        "MINUTE_MICROSECOND" => minutes * 100_000_000 + secs * 1_000_000 + micros,
+        "MINUTE_SECOND" => minutes * 100 + secs,
+        "HOUR_MICROSECOND" => {
+            hours * 10_000_000_000 + minutes * 100_000_000 + secs * 1_000_000 + micros
+        }
+        "HOUR_SECOND" => hours * 10_000 + minutes * 100 + secs,
+        "HOUR_MINUTE" => hours * 100 + minutes,
+        unit => return Err(box_err!("invalid unit {} for a TIME value", unit)),
+    };
+    let val = if dur.to_nanos() < 0 { -val } else { val };
+    Ok(Some(val))
+}
+
+/// Converts a `Duration` into whole seconds, implementing `TIME_TO_SEC`.
+/// The fractional part is discarded, matching the integer-typed signature
+/// TiDB pushes down.
+#[rpn_fn]
+#[inline]
+pub fn time_to_sec(arg: &Option<Duration>) -> Result<Option<Int>> {
+    Ok(arg.map(|dur| Int::from(dur.to_secs())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tipb::expression::ScalarFuncSig;
+
+    use crate::coprocessor::dag::rpn_expr::types::test_util::RpnFnScalarEvaluator;
+
+    #[test]
+    fn test_sec_to_time() {
+        let test_cases = vec![
+            ("2378", "00:39:38", 0),
+            ("-2378.5", "-00:39:38.5", 1),
+            ("1.123456789", "00:00:01.123457", 6),
+            ("0", "00:00:00", 0),
+            // out of range, clamped at the boundary
+            ("5000000", "838:59:59", 0),
+            ("-5000000.99", "-838:59:59.99", 2),
+        ];
+
+        for (arg, expect, fsp) in test_cases {
+            let arg = arg.parse::<Decimal>().unwrap();
+            let expect = Duration::parse(expect.as_bytes(), fsp).unwrap();
+            let output: Option<Duration> = RpnFnScalarEvaluator::new()
+                .push_param(arg.clone())
+                .evaluate(ScalarFuncSig::SecToTime)
+                .unwrap();
+            assert_eq!(output, Some(expect), "{:?}", arg);
+        }
+
+        let output: Option<Duration> = RpnFnScalarEvaluator::new()
+            .push_param(Option::<Decimal>::None)
+            .evaluate(ScalarFuncSig::SecToTime)
+            .unwrap();
+        assert_eq!(output, None);
+    }
+
+    #[test]
+    fn test_extract_duration() {
+        let test_cases: Vec<(&str, &str, i64)> = vec![
+            ("MICROSECOND", "10:20:30.123456", 123456),
+            ("SECOND", "10:20:30.123456", 30),
+            ("MINUTE", "10:20:30", 20),
+            ("HOUR", "100:20:30", 100),
+            ("SECOND_MICROSECOND", "10:20:30.123456", 30123456),
+            ("MINUTE_MICROSECOND", "10:20:30.123456", 2030123456),
+            ("MINUTE_SECOND", "10:20:30", 2030),
+            ("HOUR_MICROSECOND", "10:20:30.123456", 102030123456),
+            ("HOUR_SECOND", "10:20:30", 102030),
+            ("HOUR_MINUTE", "10:20:30", 1020),
+            ("hour_minute", "-10:20:30", -1020),
+            ("MICROSECOND", "-00:00:00.5", -500000),
+        ];
+        for (unit, arg, expect) in test_cases {
+            let arg = Duration::parse(arg.as_bytes(), 6).unwrap();
+            let output = RpnFnScalarEvaluator::new()
+                .push_param(unit.as_bytes().to_vec())
+                .push_param(arg)
+                .evaluate(ScalarFuncSig::ExtractDuration)
+                .unwrap();
+            assert_eq!(output, Some(expect), "{} {:?}", unit, arg);
+        }
+
+        // units above HOUR are not defined for a TIME value
+        let output = RpnFnScalarEvaluator::new()
+            .push_param(b"DAY".to_vec())
+            .push_param(Duration::parse(b"10:20:30", 0).unwrap())
+            .evaluate::<Int>(ScalarFuncSig::ExtractDuration);
+        assert!(output.is_err());
+
+        let output: Option<Int> = RpnFnScalarEvaluator::new()
+            .push_param(Option::<Bytes>::None)
+            .push_param(Option::<Duration>::None)
+            .evaluate(ScalarFuncSig::ExtractDuration)
+            .unwrap();
+        assert_eq!(output, None);
+    }
+
+    #[test]
+    fn test_time_to_sec() {
+        let test_cases = vec![
+            ("23:00:00", 0, 82800),
+            ("00:39:38", 0, 2378),
+            ("-00:39:38.53", 2, -2378),
+            ("00:00:00.5", 1, 0),
+            ("838:59:59", 0, 3020399),
+        ];
+
+        for (arg, fsp, expect) in test_cases {
+            let arg = Duration::parse(arg.as_bytes(), fsp).unwrap();
+            let output = RpnFnScalarEvaluator::new()
+                .push_param(arg)
+                .evaluate(ScalarFuncSig::TimeToSec)
+                .unwrap();
+            assert_eq!(output, Some(expect), "{:?}", arg);
+        }
+
+        let output: Option<Int> = RpnFnScalarEvaluator::new()
+            .push_param(Option::<Duration>::None)
+            .evaluate(ScalarFuncSig::TimeToSec)
+            .unwrap();
+        assert_eq!(output, None);
+    }
+}
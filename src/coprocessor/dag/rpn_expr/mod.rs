@@ -9,6 +9,7 @@ pub mod impl_control;
 pub mod impl_like;
 pub mod impl_math;
 pub mod impl_op;
+pub mod impl_time;
 
 pub use self::types::*;
 
@@ -24,6 +25,7 @@ use self::impl_control::*;
 use self::impl_like::*;
 use self::impl_math::*;
 use self::impl_op::*;
+use self::impl_time::*;
 
 fn map_int_sig<F>(value: ScalarFuncSig, children: &[Expr], mapper: F) -> Result<RpnFnMeta>
 where
@@ -206,6 +208,9 @@ fn map_pb_sig_to_rpn_func(value: ScalarFuncSig, children: &[Expr]) -> Result<Rpn
         ScalarFuncSig::InTime => compare_in_fn_meta::<DateTime>(),
         ScalarFuncSig::InDuration => compare_in_fn_meta::<Duration>(),
         ScalarFuncSig::InJson => compare_in_fn_meta::<Json>(),
+        ScalarFuncSig::ExtractDuration => extract_duration_fn_meta(),
+        ScalarFuncSig::SecToTime => sec_to_time_fn_meta(),
+        ScalarFuncSig::TimeToSec => time_to_sec_fn_meta(),
         _ => return Err(box_err!(
             "ScalarFunction {:?} is not supported in batch mode",
             value
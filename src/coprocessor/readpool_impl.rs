@@ -8,6 +8,7 @@ use crate::server::readpool::{self, Builder, Config, ReadPool};
 use crate::storage::kv::{destroy_tls_engine, set_tls_engine};
 use crate::storage::Engine;
 use tikv_util::collections::HashMap;
+use tikv_util::io_tags::{add_io_bytes, IOOp, IOType};
 use tikv_util::worker::FutureScheduler;
 
 use super::metrics::*;
@@ -109,6 +110,9 @@ fn tls_flush(pd_sender: &FutureScheduler<PdTask>) {
         let read_stats = cop_metrics.local_cop_flow_stats.clone();
         cop_metrics.local_cop_flow_stats = HashMap::default();
 
+        let read_bytes = read_stats.values().map(|s| s.read_bytes as u64).sum();
+        add_io_bytes(IOType::ForegroundRead, IOOp::Read, read_bytes);
+
         let result = pd_sender.schedule(PdTask::ReadStats { read_stats });
         if let Err(e) = result {
             error!("Failed to send cop pool read flow statistics"; "err" => ?e);
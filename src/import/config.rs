@@ -3,12 +3,16 @@
 use std::error::Error;
 use std::result::Result;
 
+use tikv_util::config::ReadableSize;
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 #[serde(default)]
 #[serde(rename_all = "kebab-case")]
 pub struct Config {
     pub num_threads: usize,
     pub stream_channel_window: usize,
+    /// Bytes of SST data written per second at most, 0 means unlimited.
+    pub bandwidth_limit: ReadableSize,
 }
 
 impl Default for Config {
@@ -16,6 +20,7 @@ impl Default for Config {
         Config {
             num_threads: 8,
             stream_channel_window: 128,
+            bandwidth_limit: ReadableSize(0),
         }
     }
 }
@@ -11,6 +11,7 @@ use uuid::Uuid;
 
 use engine::rocks::util::{get_cf_handle, prepare_sst_for_ingestion, validate_sst_for_ingestion};
 use engine::rocks::{IngestExternalFileOptions, DB};
+use tikv_util::io_tags::{add_io_bytes, request_io_bytes, IOOp, IOType};
 
 use super::{Error, Result};
 
@@ -216,8 +217,10 @@ impl ImportFile {
     }
 
     pub fn append(&mut self, data: &[u8]) -> Result<()> {
+        request_io_bytes(IOType::Import, data.len());
         self.file.as_mut().unwrap().write_all(data)?;
         self.digest.write(data);
+        add_io_bytes(IOType::Import, IOOp::Write, data.len() as u64);
         Ok(())
     }
 
@@ -15,6 +15,7 @@ use kvproto::raft_cmdpb::*;
 use crate::raftstore::store::Callback;
 use crate::server::transport::RaftStoreRouter;
 use tikv_util::future::paired_future_callback;
+use tikv_util::io_tags::{set_io_budget, IOType};
 use tikv_util::time::Instant;
 
 use super::import_mode::*;
@@ -47,6 +48,7 @@ impl<Router: RaftStoreRouter> ImportSSTService<Router> {
             .name_prefix("sst-importer")
             .pool_size(cfg.num_threads)
             .create();
+        set_io_budget(IOType::Import, cfg.bandwidth_limit.0 as i64);
         ImportSSTService {
             cfg,
             router,
@@ -23,6 +23,24 @@ lazy_static! {
     .unwrap();
     pub static ref STORE_SIZE_GAUGE_VEC: IntGaugeVec =
         register_int_gauge_vec!("tikv_store_size_bytes", "Size of storage.", &["type"]).unwrap();
+    pub static ref STORE_CPU_USAGE_GAUGE_VEC: IntGaugeVec = register_int_gauge_vec!(
+        "tikv_store_cpu_usage",
+        "CPU usage of each thread pool, in percent.",
+        &["pool"]
+    )
+    .unwrap();
+    pub static ref STORE_QPS_GAUGE_VEC: IntGaugeVec = register_int_gauge_vec!(
+        "tikv_store_qps",
+        "Keys read or written per second on this store.",
+        &["type"]
+    )
+    .unwrap();
+    pub static ref STORE_DISK_LATENCY_GAUGE_VEC: IntGaugeVec = register_int_gauge_vec!(
+        "tikv_store_disk_latency_us",
+        "Average latency of engine operations, in microseconds.",
+        &["type"]
+    )
+    .unwrap();
     pub static ref REGION_READ_KEYS_HISTOGRAM: Histogram = register_histogram!(
         "tikv_region_read_keys",
         "Histogram of keys written for regions",
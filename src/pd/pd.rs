@@ -22,16 +22,23 @@ use super::metrics::*;
 use crate::pd::{Error, PdClient, RegionStat};
 use crate::raftstore::coprocessor::{get_region_approximate_keys, get_region_approximate_size};
 use crate::raftstore::store::cmd_resp::new_error;
+use crate::raftstore::store::keys::{data_end_key, data_key};
 use crate::raftstore::store::util::is_epoch_stale;
 use crate::raftstore::store::util::KeysInfoFormatter;
 use crate::raftstore::store::Callback;
 use crate::raftstore::store::StoreInfo;
 use crate::raftstore::store::{CasualMessage, PeerMsg, RaftCommand, RaftRouter};
+use crate::server::block_cache_warmup;
 use crate::storage::FlowStatistics;
 use tikv_util::collections::HashMap;
 use tikv_util::time::time_now_sec;
 use tikv_util::worker::{FutureRunnable as Runnable, FutureScheduler as Scheduler, Stopped};
 
+/// Seconds between two persists of the hot region ranges.
+const HOT_RANGES_PERSIST_INTERVAL_SEC: u64 = 60;
+/// How many of the hottest regions are persisted for block cache warm-up.
+const HOT_RANGES_TOP_N: usize = 64;
+
 /// Uses an asynchronous thread to tell PD something.
 pub enum Task {
     AskSplit {
@@ -75,6 +82,12 @@ pub enum Task {
     ReadStats {
         read_stats: HashMap<u64, FlowStatistics>,
     },
+    UpdateExtendedStats {
+        // CPU usage of each thread pool, in percent.
+        cpu_usages: HashMap<String, u64>,
+        // Average latency of engine operations, in microseconds.
+        disk_latencies: Vec<(String, u64)>,
+    },
     DestroyPeer {
         region_id: u64,
     },
@@ -83,8 +96,12 @@ pub enum Task {
 pub struct StoreStat {
     pub engine_total_bytes_read: u64,
     pub engine_total_keys_read: u64,
+    pub engine_total_bytes_written: u64,
+    pub engine_total_keys_written: u64,
     pub engine_last_total_bytes_read: u64,
     pub engine_last_total_keys_read: u64,
+    pub engine_last_total_bytes_written: u64,
+    pub engine_last_total_keys_written: u64,
     pub last_report_ts: u64,
 
     pub region_bytes_read: LocalHistogram,
@@ -104,8 +121,12 @@ impl Default for StoreStat {
             last_report_ts: 0,
             engine_total_bytes_read: 0,
             engine_total_keys_read: 0,
+            engine_total_bytes_written: 0,
+            engine_total_keys_written: 0,
             engine_last_total_bytes_read: 0,
             engine_last_total_keys_read: 0,
+            engine_last_total_bytes_written: 0,
+            engine_last_total_keys_written: 0,
         }
     }
 }
@@ -119,6 +140,8 @@ pub struct PeerStat {
     pub last_written_bytes: u64,
     pub last_written_keys: u64,
     pub last_report_ts: u64,
+    pub start_key: Vec<u8>,
+    pub end_key: Vec<u8>,
 }
 
 impl Display for Task {
@@ -170,6 +193,9 @@ impl Display for Task {
             Task::ReadStats { ref read_stats } => {
                 write!(f, "get the read statistics {:?}", read_stats)
             }
+            Task::UpdateExtendedStats { ref cpu_usages, .. } => {
+                write!(f, "update extended stats, cpu usages {:?}", cpu_usages)
+            }
             Task::DestroyPeer { ref region_id } => {
                 write!(f, "destroy peer of region {}", region_id)
             }
@@ -187,6 +213,8 @@ pub struct Runner<T: PdClient> {
     is_hb_receiver_scheduled: bool,
     // Seconds between when a region is expected to send a heartbeat.
     region_heartbeat_interval: u64,
+    // When the hot region ranges were last persisted for block cache warm-up.
+    last_hot_ranges_persist_ts: u64,
 
     // use for Runner inner handle function to send Task to itself
     // actually it is the sender connected to Runner's Worker which
@@ -212,6 +240,7 @@ impl<T: PdClient> Runner<T> {
             region_peers: HashMap::default(),
             store_stat: StoreStat::default(),
             region_heartbeat_interval,
+            last_hot_ranges_persist_ts: 0,
             scheduler,
         }
     }
@@ -411,18 +440,38 @@ impl<T: PdClient> Runner<T> {
         }
 
         stats.set_available(available);
+        let keys_read =
+            self.store_stat.engine_total_keys_read - self.store_stat.engine_last_total_keys_read;
+        let keys_written = self.store_stat.engine_total_keys_written
+            - self.store_stat.engine_last_total_keys_written;
         stats.set_bytes_read(
             self.store_stat.engine_total_bytes_read - self.store_stat.engine_last_total_bytes_read,
         );
-        stats.set_keys_read(
-            self.store_stat.engine_total_keys_read - self.store_stat.engine_last_total_keys_read,
+        stats.set_keys_read(keys_read);
+        stats.set_bytes_written(
+            self.store_stat.engine_total_bytes_written
+                - self.store_stat.engine_last_total_bytes_written,
         );
+        stats.set_keys_written(keys_written);
         let mut interval = pdpb::TimeInterval::new();
         interval.set_start_timestamp(self.store_stat.last_report_ts);
         stats.set_interval(interval);
         self.store_stat.engine_last_total_bytes_read = self.store_stat.engine_total_bytes_read;
         self.store_stat.engine_last_total_keys_read = self.store_stat.engine_total_keys_read;
-        self.store_stat.last_report_ts = time_now_sec();
+        self.store_stat.engine_last_total_bytes_written =
+            self.store_stat.engine_total_bytes_written;
+        self.store_stat.engine_last_total_keys_written = self.store_stat.engine_total_keys_written;
+        let now = time_now_sec();
+        let report_interval = now - self.store_stat.last_report_ts;
+        if report_interval > 0 {
+            STORE_QPS_GAUGE_VEC
+                .with_label_values(&["read"])
+                .set((keys_read / report_interval) as i64);
+            STORE_QPS_GAUGE_VEC
+                .with_label_values(&["write"])
+                .set((keys_written / report_interval) as i64);
+        }
+        self.store_stat.last_report_ts = now;
         self.store_stat.region_bytes_written.flush();
         self.store_stat.region_keys_written.flush();
         self.store_stat.region_bytes_read.flush();
@@ -439,6 +488,41 @@ impl<T: PdClient> Runner<T> {
             error!("store heartbeat failed"; "err" => ?e);
         });
         handle.spawn(f);
+
+        self.maybe_persist_hot_ranges();
+    }
+
+    /// Persists the ranges of the regions with the highest read flow beside
+    /// the kv engine, so the next start can warm up the block cache with them.
+    fn maybe_persist_hot_ranges(&mut self) {
+        let now = time_now_sec();
+        if now < self.last_hot_ranges_persist_ts + HOT_RANGES_PERSIST_INTERVAL_SEC {
+            return;
+        }
+        self.last_hot_ranges_persist_ts = now;
+
+        let mut hot_peers: Vec<_> = self
+            .region_peers
+            .values()
+            // `last_report_ts` is only set by region heartbeats, which are
+            // also what keep `start_key` and `end_key` up to date.
+            .filter(|stat| stat.read_bytes > 0 && stat.last_report_ts > 0)
+            .collect();
+        hot_peers.sort_by(|a, b| b.read_bytes.cmp(&a.read_bytes));
+        hot_peers.truncate(HOT_RANGES_TOP_N);
+        let ranges: Vec<_> = hot_peers
+            .into_iter()
+            .map(|stat| (data_key(&stat.start_key), data_end_key(&stat.end_key)))
+            .collect();
+
+        let path = block_cache_warmup::hot_ranges_path(&self.db);
+        if let Err(e) = block_cache_warmup::persist_hot_ranges(&path, &ranges) {
+            warn!(
+                "failed to persist hot ranges";
+                "path" => %path.display(),
+                "err" => %e,
+            );
+        }
     }
 
     fn handle_report_batch_split(&self, handle: &Handle, regions: Vec<metapb::Region>) {
@@ -621,6 +705,26 @@ impl<T: PdClient> Runner<T> {
         }
     }
 
+    /// Updates the extended store stats collected by the stats monitor.
+    /// `StoreStats` of the current PD version can't carry them, so they are
+    /// only exported as metrics for now.
+    fn handle_update_extended_stats(
+        &mut self,
+        cpu_usages: HashMap<String, u64>,
+        disk_latencies: Vec<(String, u64)>,
+    ) {
+        for (pool, usage) in &cpu_usages {
+            STORE_CPU_USAGE_GAUGE_VEC
+                .with_label_values(&[pool])
+                .set(*usage as i64);
+        }
+        for (op, latency) in &disk_latencies {
+            STORE_DISK_LATENCY_GAUGE_VEC
+                .with_label_values(&[op])
+                .set(*latency as i64);
+        }
+    }
+
     fn handle_destroy_peer(&mut self, region_id: u64) {
         match self.region_peers.remove(&region_id) {
             None => return,
@@ -696,6 +800,8 @@ impl<T: PdClient> Runnable<Task> for Runner<T> {
                     peer_stat.last_read_bytes = peer_stat.read_bytes;
                     peer_stat.last_read_keys = peer_stat.read_keys;
                     peer_stat.last_report_ts = time_now_sec();
+                    peer_stat.start_key = region.get_start_key().to_vec();
+                    peer_stat.end_key = region.get_end_key().to_vec();
                     last_report_ts = cmp::max(
                         last_report_ts,
                         peer_stat.last_report_ts - self.region_heartbeat_interval,
@@ -708,6 +814,8 @@ impl<T: PdClient> Runnable<Task> for Runner<T> {
                         last_report_ts,
                     )
                 };
+                self.store_stat.engine_total_bytes_written += written_bytes_delta;
+                self.store_stat.engine_total_keys_written += written_keys_delta;
                 self.handle_heartbeat(
                     handle,
                     region,
@@ -735,6 +843,10 @@ impl<T: PdClient> Runnable<Task> for Runner<T> {
                 merge_source,
             } => self.handle_validate_peer(handle, region, peer, merge_source),
             Task::ReadStats { read_stats } => self.handle_read_stats(read_stats),
+            Task::UpdateExtendedStats {
+                cpu_usages,
+                disk_latencies,
+            } => self.handle_update_extended_stats(cpu_usages, disk_latencies),
             Task::DestroyPeer { region_id } => self.handle_destroy_peer(region_id),
         };
     }
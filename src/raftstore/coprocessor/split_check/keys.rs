@@ -189,7 +189,7 @@ pub fn get_region_approximate_keys(db: &DB, region: &Region) -> Result<u64> {
     let start = keys::enc_start_key(region);
     let end = keys::enc_end_key(region);
     let cf = box_try!(rocks::util::get_cf_handle(db, CF_WRITE));
-    let (_, keys) = get_range_entries_and_versions(db, cf, &start, &end).unwrap_or_default();
+    let (_, keys, _) = get_range_entries_and_versions(db, cf, &start, &end).unwrap_or_default();
     Ok(keys)
 }
 
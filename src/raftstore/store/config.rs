@@ -2,11 +2,35 @@
 
 use std::time::Duration;
 use std::u64;
+use chrono::NaiveTime;
 use time::Duration as TimeDuration;
 
 use crate::raftstore::{coprocessor, Result};
 use tikv_util::config::{ReadableDuration, ReadableSize};
 
+/// Parses a time-of-day window like `"01:30-05:00"`. An empty or malformed
+/// string yields `None`. The two bounds may wrap past midnight but must not
+/// be equal, since an empty window is better expressed by disabling it.
+fn parse_schedule_window(window: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let mut parts = window.splitn(2, '-');
+    let start = NaiveTime::parse_from_str(parts.next()?, "%H:%M").ok()?;
+    let end = NaiveTime::parse_from_str(parts.next()?, "%H:%M").ok()?;
+    if start == end {
+        return None;
+    }
+    Some((start, end))
+}
+
+/// Checks whether `now` falls inside the window, handling windows that wrap
+/// past midnight (e.g. `22:00-04:00`).
+pub fn in_schedule_window(now: NaiveTime, (start, end): (NaiveTime, NaiveTime)) -> bool {
+    if start < end {
+        start <= now && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 #[serde(rename_all = "kebab-case")]
@@ -61,6 +85,12 @@ pub struct Config {
     /// Minimum percentage of tombstones to trigger manual compaction.
     /// Should between 1 and 100.
     pub region_compact_tombstones_percent: u64,
+    /// Low-traffic window in which a scheduled compaction pass over the
+    /// whole store may run, in "HH:MM-HH:MM" (store local time). The window
+    /// may wrap past midnight. An empty string disables the pass.
+    pub region_compact_schedule_window: String,
+    /// Restricts the scheduled compaction pass to the bottommost level.
+    pub region_compact_schedule_bottommost: bool,
     pub pd_heartbeat_tick_interval: ReadableDuration,
     pub pd_store_heartbeat_tick_interval: ReadableDuration,
     pub snap_mgr_gc_tick_interval: ReadableDuration,
@@ -161,6 +191,8 @@ impl Default for Config {
             region_compact_check_step: 100,
             region_compact_min_tombstones: 10000,
             region_compact_tombstones_percent: 30,
+            region_compact_schedule_window: String::new(),
+            region_compact_schedule_bottommost: false,
             pd_heartbeat_tick_interval: ReadableDuration::minutes(1),
             pd_store_heartbeat_tick_interval: ReadableDuration::secs(10),
             notify_capacity: 40960,
@@ -214,6 +246,12 @@ impl Config {
         self.raft_base_tick_interval.0 * self.raft_heartbeat_ticks as u32
     }
 
+    /// Returns the parsed scheduled compaction window, or `None` if the
+    /// feature is disabled by an empty `region-compact-schedule-window`.
+    pub fn compact_schedule_window(&self) -> Option<(NaiveTime, NaiveTime)> {
+        parse_schedule_window(&self.region_compact_schedule_window)
+    }
+
     pub fn validate(&mut self) -> Result<()> {
         if self.raft_heartbeat_ticks == 0 {
             return Err(box_err!("heartbeat tick must greater than 0"));
@@ -327,6 +365,15 @@ impl Config {
             ));
         }
 
+        if !self.region_compact_schedule_window.is_empty()
+            && parse_schedule_window(&self.region_compact_schedule_window).is_none()
+        {
+            return Err(box_err!(
+                "region-compact-schedule-window expects \"HH:MM-HH:MM\", current value is {}",
+                self.region_compact_schedule_window
+            ));
+        }
+
         if self.local_read_batch_size == 0 {
             return Err(box_err!("local-read-batch-size must be greater than 0"));
         }
@@ -617,5 +664,42 @@ mod tests {
         cfg = Config::new();
         cfg.future_poll_size = 0;
         assert!(cfg.validate().is_err());
+
+        cfg = Config::new();
+        cfg.region_compact_schedule_window = "01:30".to_owned();
+        assert!(cfg.validate().is_err());
+        cfg.region_compact_schedule_window = "01:30-05:61".to_owned();
+        assert!(cfg.validate().is_err());
+        cfg.region_compact_schedule_window = "01:30-01:30".to_owned();
+        assert!(cfg.validate().is_err());
+        cfg.region_compact_schedule_window = "01:30-05:00".to_owned();
+        cfg.validate().unwrap();
+    }
+
+    #[test]
+    fn test_compact_schedule_window() {
+        let mut cfg = Config::new();
+        assert!(cfg.compact_schedule_window().is_none());
+
+        cfg.region_compact_schedule_window = "22:00-04:00".to_owned();
+        let window = cfg.compact_schedule_window().unwrap();
+        let cases = vec![
+            ("23:30", true),
+            ("22:00", true),
+            ("03:59", true),
+            ("04:00", false),
+            ("12:00", false),
+        ];
+        for (now, expect) in cases {
+            let now = NaiveTime::parse_from_str(now, "%H:%M").unwrap();
+            assert_eq!(in_schedule_window(now, window), expect, "{}", now);
+        }
+
+        cfg.region_compact_schedule_window = "01:00-05:00".to_owned();
+        let window = cfg.compact_schedule_window().unwrap();
+        let now = NaiveTime::parse_from_str("03:00", "%H:%M").unwrap();
+        assert!(in_schedule_window(now, window));
+        let now = NaiveTime::parse_from_str("06:00", "%H:%M").unwrap();
+        assert!(!in_schedule_window(now, window));
     }
 }
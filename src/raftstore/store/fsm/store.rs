@@ -1,5 +1,6 @@
 // Copyright 2016 TiKV Project Authors. Licensed under Apache-2.0.
 
+use chrono::Local;
 use crossbeam::channel::{TryRecvError, TrySendError};
 use engine::rocks;
 use engine::rocks::CompactionJobInfo;
@@ -25,7 +26,7 @@ use crate::import::SSTImporter;
 use crate::pd::{PdClient, PdRunner, PdTask};
 use crate::raftstore::coprocessor::split_observer::SplitObserver;
 use crate::raftstore::coprocessor::{CoprocessorHost, RegionChangeEvent};
-use crate::raftstore::store::config::Config;
+use crate::raftstore::store::config::{in_schedule_window, Config};
 use crate::raftstore::store::fsm::metrics::*;
 use crate::raftstore::store::fsm::peer::{
     maybe_destroy_source, new_admin_request, PeerFsm, PeerFsmDelegate,
@@ -57,6 +58,7 @@ use crate::storage::kv::{CompactedEvent, CompactionListener};
 use engine::Engines;
 use engine::{Iterable, Mutable, Peekable};
 use tikv_util::collections::{HashMap, HashSet};
+use tikv_util::io_tags::{add_io_bytes, IOOp, IOType};
 use tikv_util::mpsc::{self, LooseBoundedSender, Receiver};
 use tikv_util::time::{duration_to_sec, SlowTimer};
 use tikv_util::timer::SteadyTimer;
@@ -392,6 +394,7 @@ impl<'a, T: Transport, C: PdClient> StoreFsmDelegate<'a, T, C> {
             StoreTick::SnapGc => self.on_snap_mgr_gc(),
             StoreTick::CompactLockCf => self.on_compact_lock_cf(),
             StoreTick::CompactCheck => self.on_compact_check_tick(),
+            StoreTick::CompactScheduleWindow => self.on_compact_schedule_window_tick(),
             StoreTick::ConsistencyCheck => self.on_consistency_check_tick(),
             StoreTick::CleanupImportSST => self.on_cleanup_import_sst_tick(),
         }
@@ -441,6 +444,7 @@ impl<'a, T: Transport, C: PdClient> StoreFsmDelegate<'a, T, C> {
         self.fsm.store.start_time = Some(time::get_time());
         self.register_cleanup_import_sst_tick();
         self.register_compact_check_tick();
+        self.register_compact_schedule_window_tick();
         self.register_pd_store_heartbeat_tick();
         self.register_compact_lock_cf_tick();
         self.register_snap_mgr_gc_tick();
@@ -507,6 +511,7 @@ impl<T: Transport, C: PdClient> RaftPoller<T, C> {
                     panic!("{} failed to save raft append result: {:?}", self.tag, e);
                 });
             let data_size = self.poll_ctx.raft_wb.data_size();
+            add_io_bytes(IOType::Raft, IOOp::Write, data_size as u64);
             if data_size > RAFT_WB_SHRINK_SIZE {
                 self.poll_ctx.raft_wb = WriteBatch::with_capacity(4 * 1024);
             } else {
@@ -1549,6 +1554,7 @@ impl<'a, T: Transport, C: PdClient> StoreFsmDelegate<'a, T, C> {
                 ranges: ranges_need_check,
                 tombstones_num_threshold: self.ctx.cfg.region_compact_min_tombstones,
                 tombstones_percent_threshold: self.ctx.cfg.region_compact_tombstones_percent,
+                bottommost_only: false,
             })
         {
             error!(
@@ -1559,6 +1565,75 @@ impl<'a, T: Transport, C: PdClient> StoreFsmDelegate<'a, T, C> {
         }
     }
 
+    fn register_compact_schedule_window_tick(&self) {
+        self.ctx.schedule_store_tick(
+            StoreTick::CompactScheduleWindow,
+            self.ctx.cfg.region_compact_check_interval.0,
+        )
+    }
+
+    /// Runs a store-wide compaction pass while inside the configured
+    /// low-traffic window. Unlike the regular compact check, which steps
+    /// through a few regions per tick, this inspects all region ranges at
+    /// once; the compact worker still only compacts the ranges whose table
+    /// properties show enough tombstones or stale MVCC versions, so quiet
+    /// ranges stay untouched.
+    fn on_compact_schedule_window_tick(&mut self) {
+        self.register_compact_schedule_window_tick();
+        let window = match self.ctx.cfg.compact_schedule_window() {
+            Some(window) => window,
+            None => return,
+        };
+        if !in_schedule_window(Local::now().time(), window) {
+            return;
+        }
+        if self.ctx.compact_scheduler.is_busy() {
+            debug!(
+                "compact worker is busy, delay scheduled compaction";
+                "store_id" => self.fsm.store.id,
+            );
+            return;
+        }
+        if rocks::util::auto_compactions_is_disabled(&self.ctx.engines.kv) {
+            debug!(
+                "skip scheduled compaction when disabled auto compactions";
+                "store_id" => self.fsm.store.id,
+            );
+            return;
+        }
+
+        let mut ranges_need_check = vec![keys::DATA_MIN_KEY.to_vec()];
+        {
+            let meta = self.ctx.store_meta.lock().unwrap();
+            if meta.region_ranges.is_empty() {
+                return;
+            }
+            ranges_need_check.extend(meta.region_ranges.keys().cloned());
+        }
+        if ranges_need_check.last().map(Vec::as_slice) != Some(keys::DATA_MAX_KEY) {
+            ranges_need_check.push(keys::DATA_MAX_KEY.to_vec());
+        }
+
+        let cf_names = vec![CF_DEFAULT.to_owned(), CF_WRITE.to_owned()];
+        if let Err(e) = self
+            .ctx
+            .compact_scheduler
+            .schedule(CompactTask::CheckAndCompact {
+                cf_names,
+                ranges: ranges_need_check,
+                tombstones_num_threshold: self.ctx.cfg.region_compact_min_tombstones,
+                tombstones_percent_threshold: self.ctx.cfg.region_compact_tombstones_percent,
+                bottommost_only: self.ctx.cfg.region_compact_schedule_bottommost,
+            })
+        {
+            error!(
+                "schedule window compaction task failed";
+                "store_id" => self.fsm.store.id,
+                "err" => ?e,
+            );
+        }
+    }
+
     fn store_heartbeat_pd(&mut self) {
         let mut stats = StoreStats::new();
 
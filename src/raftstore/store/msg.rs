@@ -120,6 +120,7 @@ impl PeerTicks {
 #[derive(Debug, Clone, Copy)]
 pub enum StoreTick {
     CompactCheck,
+    CompactScheduleWindow,
     PdStoreHeartbeat,
     SnapGc,
     CompactLockCf,
@@ -132,6 +133,7 @@ impl StoreTick {
     pub fn tag(self) -> &'static str {
         match self {
             StoreTick::CompactCheck => "compact_check",
+            StoreTick::CompactScheduleWindow => "compact_schedule_window",
             StoreTick::PdStoreHeartbeat => "pd_store_heartbeat",
             StoreTick::SnapGc => "snap_gc",
             StoreTick::CompactLockCf => "compact_lock_cf",
@@ -11,6 +11,7 @@ use engine::rocks::{
 };
 use engine::Iterable;
 use tikv_util::codec::bytes::{BytesEncoder, CompactBytesFromFileDecoder};
+use tikv_util::io_tags::{add_io_bytes, IOOp, IOType, TaggedReader, TaggedWriter};
 
 use super::Error;
 
@@ -35,7 +36,8 @@ pub fn build_plain_cf_file(
     start_key: &[u8],
     end_key: &[u8],
 ) -> Result<BuildStatistics, Error> {
-    let mut file = box_try!(OpenOptions::new().write(true).create_new(true).open(path));
+    let file = box_try!(OpenOptions::new().write(true).create_new(true).open(path));
+    let mut file = TaggedWriter::new(IOType::Snapshot, file);
     let mut stats = BuildStatistics::default();
     box_try!(snap.scan_cf(cf, start_key, end_key, false, |key, value| {
         stats.key_count += 1;
@@ -47,7 +49,7 @@ pub fn build_plain_cf_file(
     if stats.key_count > 0 {
         // use an empty byte array to indicate that cf reaches an end.
         box_try!(file.encode_compact_bytes(b""));
-        box_try!(file.sync_all());
+        box_try!(file.into_inner().sync_all());
     } else {
         drop(file);
         box_try!(fs::remove_file(path));
@@ -92,6 +94,7 @@ pub fn build_sst_cf_file(
     if stats.key_count > 0 {
         box_try!(sst_writer.finish());
         box_try!(File::open(path).and_then(|f| f.sync_all()));
+        add_io_bytes(IOType::Snapshot, IOOp::Write, stats.total_size as u64);
     } else {
         box_try!(fs::remove_file(path));
     }
@@ -106,7 +109,8 @@ pub fn apply_plain_cf_file(
     cf: &str,
     batch_size: usize,
 ) -> Result<(), Error> {
-    let mut decoder = BufReader::new(box_try!(File::open(path)));
+    let file = TaggedReader::new(IOType::Snapshot, box_try!(File::open(path)));
+    let mut decoder = BufReader::new(file);
     let cf_handle = box_try!(get_cf_handle(&db, cf));
     let wb = WriteBatch::new();
     loop {
@@ -8,6 +8,7 @@ use std::time::Instant;
 
 use engine::rocks;
 use engine::rocks::util::compact_range;
+use engine::rocks::{CompactOptions, DBBottommostLevelCompaction};
 use engine::CF_WRITE;
 use engine::DB;
 use tikv_util::worker::Runnable;
@@ -29,6 +30,7 @@ pub enum Task {
         ranges: Vec<Key>,              // Ranges need to check
         tombstones_num_threshold: u64, // The minimum RocksDB tombstones a range that need compacting has
         tombstones_percent_threshold: u64,
+        bottommost_only: bool, // Restrict the compaction to the bottommost level
     },
 }
 
@@ -53,6 +55,7 @@ impl Display for Task {
                 ref ranges,
                 tombstones_num_threshold,
                 tombstones_percent_threshold,
+                bottommost_only,
             } => f
                 .debug_struct("CheckAndCompact")
                 .field("cf_names", cf_names)
@@ -68,6 +71,7 @@ impl Display for Task {
                     "tombstones_percent_threshold",
                     &tombstones_percent_threshold,
                 )
+                .field("bottommost_only", &bottommost_only)
                 .finish(),
         }
     }
@@ -95,31 +99,45 @@ impl Runner {
     }
 
     /// Sends a compact range command to RocksDB to compact the range of the cf.
+    /// With `bottommost_only` the bottommost level is forced to be rewritten,
+    /// so tombstones and stale MVCC versions already sitting there are
+    /// reclaimed even when the upper levels have nothing to push down.
     pub fn compact_range_cf(
         &mut self,
         cf_name: &str,
         start_key: Option<&[u8]>,
         end_key: Option<&[u8]>,
+        bottommost_only: bool,
     ) -> Result<(), Error> {
         let handle = box_try!(rocks::util::get_cf_handle(&self.engine, &cf_name));
         let timer = Instant::now();
         let compact_range_timer = COMPACT_RANGE_CF
             .with_label_values(&[cf_name])
             .start_coarse_timer();
-        compact_range(
-            &self.engine,
-            handle,
-            start_key,
-            end_key,
-            false,
-            1, /* threads */
-        );
+        if bottommost_only {
+            let mut compact_opts = CompactOptions::new();
+            compact_opts.set_exclusive_manual_compaction(false);
+            compact_opts.set_max_subcompactions(1);
+            compact_opts.set_bottommost_level_compaction(DBBottommostLevelCompaction::Force);
+            self.engine
+                .compact_range_cf_opt(handle, &compact_opts, start_key, end_key);
+        } else {
+            compact_range(
+                &self.engine,
+                handle,
+                start_key,
+                end_key,
+                false,
+                1, /* threads */
+            );
+        }
         compact_range_timer.observe_duration();
         info!(
             "compact range finished";
             "range_start" => start_key.map(::log_wrappers::Key),
             "range_end" => end_key.map(::log_wrappers::Key),
             "cf" => cf_name,
+            "bottommost_only" => bottommost_only,
             "time_takes" => ?timer.elapsed(),
         );
         Ok(())
@@ -139,6 +157,7 @@ impl Runnable<Task> for Runner {
                     cf,
                     start_key.as_ref().map(Vec::as_slice),
                     end_key.as_ref().map(Vec::as_slice),
+                    false,
                 ) {
                     error!("execute compact range failed"; "cf" => cf, "err" => %e);
                 }
@@ -148,6 +167,7 @@ impl Runnable<Task> for Runner {
                 ranges,
                 tombstones_num_threshold,
                 tombstones_percent_threshold,
+                bottommost_only,
             } => match collect_ranges_need_compact(
                 &self.engine,
                 ranges,
@@ -157,7 +177,9 @@ impl Runnable<Task> for Runner {
                 Ok(mut ranges) => {
                     for (start, end) in ranges.drain(..) {
                         for cf in &cf_names {
-                            if let Err(e) = self.compact_range_cf(cf, Some(&start), Some(&end)) {
+                            if let Err(e) =
+                                self.compact_range_cf(cf, Some(&start), Some(&end), bottommost_only)
+                            {
                                 error!(
                                     "compact range failed";
                                     "range_start" => log_wrappers::Key(&start),
@@ -178,15 +200,18 @@ impl Runnable<Task> for Runner {
 fn need_compact(
     num_entires: u64,
     num_versions: u64,
+    num_deletes: u64,
     tombstones_num_threshold: u64,
     tombstones_percent_threshold: u64,
 ) -> bool {
-    if num_entires <= num_versions {
+    if num_entires < num_versions {
         return false;
     }
 
     // When the number of tombstones exceed threshold and ratio, this range need compacting.
-    let estimate_num_del = num_entires - num_versions;
+    // Both RocksDB tombstones and MVCC delete marks can be dropped by a compaction, so
+    // count them together.
+    let estimate_num_del = num_entires - num_versions + num_deletes;
     estimate_num_del >= tombstones_num_threshold
         && estimate_num_del * 100 >= tombstones_percent_threshold * num_entires
 }
@@ -206,12 +231,13 @@ fn collect_ranges_need_compact(
     let mut compact_end = None;
     for range in ranges.windows(2) {
         // Get total entries and total versions in this range and checks if it needs to be compacted.
-        if let Some((num_ent, num_ver)) =
+        if let Some((num_ent, num_ver, num_del)) =
             get_range_entries_and_versions(engine, cf, &range[0], &range[1])
         {
             if need_compact(
                 num_ent,
                 num_ver,
+                num_del,
                 tombstones_num_threshold,
                 tombstones_percent_threshold,
             ) {
@@ -365,7 +391,7 @@ mod tests {
         engine.flush_cf(cf, true).unwrap();
 
         let (s, e) = (data_key(b"k0"), data_key(b"k5"));
-        let (entries, version) = get_range_entries_and_versions(&engine, cf, &s, &e).unwrap();
+        let (entries, version, _) = get_range_entries_and_versions(&engine, cf, &s, &e).unwrap();
         assert_eq!(entries, 10);
         assert_eq!(version, 5);
 
@@ -377,7 +403,7 @@ mod tests {
         engine.flush_cf(cf, true).unwrap();
 
         let (s, e) = (data_key(b"k5"), data_key(b"k9"));
-        let (entries, version) = get_range_entries_and_versions(&engine, cf, &s, &e).unwrap();
+        let (entries, version, _) = get_range_entries_and_versions(&engine, cf, &s, &e).unwrap();
         assert_eq!(entries, 5);
         assert_eq!(version, 5);
 
@@ -401,7 +427,7 @@ mod tests {
         engine.flush_cf(cf, true).unwrap();
 
         let (s, e) = (data_key(b"k5"), data_key(b"k9"));
-        let (entries, version) = get_range_entries_and_versions(&engine, cf, &s, &e).unwrap();
+        let (entries, version, _) = get_range_entries_and_versions(&engine, cf, &s, &e).unwrap();
         assert_eq!(entries, 10);
         assert_eq!(version, 5);
 
@@ -0,0 +1,165 @@
+// Copyright 2019 TiKV Project Authors. Licensed under Apache-2.0.
+
+//! Block cache warm-up across restarts.
+//!
+//! A freshly restarted TiKV starts with an empty block cache, so rolling
+//! restarts cause minutes of elevated read latency while the cache refills
+//! from disk. To soften that, the PD worker periodically persists the key
+//! ranges of the regions with the highest read flow into a small file kept
+//! beside the kv engine. On the next start we scan those ranges with
+//! `fill_cache` enabled before serving traffic, which pulls the hottest
+//! blocks back into the cache.
+//!
+//! The file is purely an optimization hint: a missing, stale or corrupted
+//! file only means a colder start, never an error.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use engine::rocks::DB;
+use engine::Iterable;
+use engine::{CF_DEFAULT, CF_WRITE};
+use tikv_util::time::Instant;
+
+/// Name of the hot ranges file, created in the kv engine directory.
+pub const HOT_RANGES_FILE: &str = "hot_ranges";
+
+/// Returns the path of the hot ranges file for the given kv engine.
+pub fn hot_ranges_path(db: &DB) -> PathBuf {
+    Path::new(db.path()).join(HOT_RANGES_FILE)
+}
+
+/// Persists the given data key ranges atomically, one hex encoded
+/// `start end` pair per line. The previous file, if any, is replaced.
+pub fn persist_hot_ranges(path: &Path, ranges: &[(Vec<u8>, Vec<u8>)]) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    {
+        let mut f = File::create(&tmp_path)?;
+        for (start, end) in ranges {
+            writeln!(f, "{} {}", hex::encode_upper(start), hex::encode_upper(end))?;
+        }
+        f.sync_all()?;
+    }
+    fs::rename(&tmp_path, path)
+}
+
+/// Loads the persisted hot ranges. Malformed lines are skipped so that a
+/// partially written or hand edited file degrades gracefully.
+pub fn load_hot_ranges(path: &Path) -> io::Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    let f = File::open(path)?;
+    let mut ranges = Vec::new();
+    for line in BufReader::new(f).lines() {
+        let line = line?;
+        let mut parts = line.split_whitespace();
+        let (start, end) = match (parts.next(), parts.next()) {
+            (Some(start), Some(end)) => (start, end),
+            _ => continue,
+        };
+        if let (Ok(start), Ok(end)) = (hex::decode(start), hex::decode(end)) {
+            ranges.push((start, end));
+        }
+    }
+    Ok(ranges)
+}
+
+/// Scans the persisted hot ranges with `fill_cache` enabled so the block
+/// cache is repopulated with the blocks that were hot before the restart.
+/// Intended to run on a background thread right after the engine is opened.
+pub fn warm_up_block_cache(db: Arc<DB>) {
+    let path = hot_ranges_path(&db);
+    let ranges = match load_hot_ranges(&path) {
+        Ok(ranges) => ranges,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return,
+        Err(e) => {
+            warn!("failed to load hot ranges, skip block cache warm-up"; "err" => %e);
+            return;
+        }
+    };
+    if ranges.is_empty() {
+        return;
+    }
+    let timer = Instant::now();
+    let mut keys = 0;
+    for (start, end) in &ranges {
+        for cf in &[CF_WRITE, CF_DEFAULT] {
+            if let Err(e) = db.scan_cf(cf, start, end, true /* fill_cache */, |_, _| {
+                keys += 1;
+                Ok(true)
+            }) {
+                warn!(
+                    "failed to scan range during block cache warm-up";
+                    "cf" => *cf,
+                    "start" => hex::encode_upper(start),
+                    "end" => hex::encode_upper(end),
+                    "err" => %e,
+                );
+            }
+        }
+    }
+    info!(
+        "block cache warm-up finished";
+        "ranges" => ranges.len(),
+        "keys" => keys,
+        "takes" => ?timer.elapsed(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use engine::rocks::util::{get_cf_handle, new_engine};
+    use engine::rocks::Writable;
+    use engine::ALL_CFS;
+    use tempfile::Builder;
+
+    #[test]
+    fn test_persist_and_load_hot_ranges() {
+        let tmp_dir = Builder::new()
+            .prefix("test_hot_ranges")
+            .tempdir()
+            .unwrap();
+        let path = tmp_dir.path().join(HOT_RANGES_FILE);
+        assert_eq!(
+            load_hot_ranges(&path).unwrap_err().kind(),
+            io::ErrorKind::NotFound
+        );
+
+        let ranges = vec![
+            (b"zk1".to_vec(), b"zk5".to_vec()),
+            (b"zk7".to_vec(), b"zk9".to_vec()),
+        ];
+        persist_hot_ranges(&path, &ranges).unwrap();
+        assert_eq!(load_hot_ranges(&path).unwrap(), ranges);
+
+        // Rewriting replaces the old content.
+        persist_hot_ranges(&path, &ranges[..1]).unwrap();
+        assert_eq!(load_hot_ranges(&path).unwrap(), &ranges[..1]);
+    }
+
+    #[test]
+    fn test_warm_up_block_cache() {
+        let tmp_dir = Builder::new()
+            .prefix("test_warm_up_block_cache")
+            .tempdir()
+            .unwrap();
+        let db = new_engine(tmp_dir.path().to_str().unwrap(), None, ALL_CFS, None).unwrap();
+        let db = Arc::new(db);
+        for cf in &[CF_WRITE, CF_DEFAULT] {
+            let handle = get_cf_handle(&db, cf).unwrap();
+            for i in 0..10u8 {
+                let k = format!("zk{:03}", i);
+                db.put_cf(handle, k.as_bytes(), b"v").unwrap();
+            }
+            db.flush_cf(handle, true).unwrap();
+        }
+
+        // No file yet, warm-up is a no-op.
+        warm_up_block_cache(Arc::clone(&db));
+
+        let ranges = vec![(b"zk000".to_vec(), b"zk005".to_vec())];
+        persist_hot_ranges(&hot_ranges_path(&db), &ranges).unwrap();
+        warm_up_block_cache(db);
+    }
+}
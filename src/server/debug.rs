@@ -844,6 +844,7 @@ impl Debugger {
             ("mvcc.max_ts", mvcc_properties.max_ts),
             ("mvcc.num_rows", mvcc_properties.num_rows),
             ("mvcc.num_puts", mvcc_properties.num_puts),
+            ("mvcc.num_deletes", mvcc_properties.num_deletes),
             ("mvcc.num_versions", mvcc_properties.num_versions),
             ("mvcc.max_row_versions", mvcc_properties.max_row_versions),
         ]
@@ -856,6 +857,78 @@ impl Debugger {
         ));
         Ok(res)
     }
+
+    /// Inspects every SST file of `cf` in the kv engine that overlaps
+    /// `[start, end)` and returns its properties keyed by file name, so SST
+    /// files can be examined without the external `sst_dump` binary, which
+    /// does not understand TiKV's key encoding. An empty `end` means the end
+    /// of the keyspace.
+    pub fn get_sst_properties(
+        &self,
+        cf: &str,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<Vec<(String, Vec<(String, String)>)>> {
+        validate_db_and_cf(DBType::KV, cf)?;
+        let db = &self.engines.kv;
+        let end = if end.is_empty() {
+            keys::data_end_key(b"")
+        } else {
+            end.to_vec()
+        };
+        let collection = box_try!(engine::util::get_range_properties_cf(db, cf, start, &end));
+
+        let mut ssts = Vec::with_capacity(collection.len());
+        for (file, v) in &*collection {
+            let mut props = vec![("num_entries".to_owned(), v.num_entries().to_string())];
+            if let Ok(range) = RangeProperties::decode(v.user_collected_properties()) {
+                if let Some(smallest) = range.smallest_key() {
+                    props.push(("smallest_key".to_owned(), escape(&smallest)));
+                }
+                if let Some(largest) = range.largest_key() {
+                    props.push(("largest_key".to_owned(), escape(&largest)));
+                }
+            }
+            if cf == CF_WRITE {
+                if let Ok(mvcc) = MvccProperties::decode(v.user_collected_properties()) {
+                    props.push(("mvcc.min_ts".to_owned(), mvcc.min_ts.to_string()));
+                    props.push(("mvcc.max_ts".to_owned(), mvcc.max_ts.to_string()));
+                    props.push(("mvcc.num_rows".to_owned(), mvcc.num_rows.to_string()));
+                    props.push(("mvcc.num_puts".to_owned(), mvcc.num_puts.to_string()));
+                    props.push(("mvcc.num_deletes".to_owned(), mvcc.num_deletes.to_string()));
+                    props.push((
+                        "mvcc.num_versions".to_owned(),
+                        mvcc.num_versions.to_string(),
+                    ));
+                    props.push((
+                        "mvcc.max_row_versions".to_owned(),
+                        mvcc.max_row_versions.to_string(),
+                    ));
+                }
+            }
+            ssts.push((file.to_owned(), props));
+        }
+        Ok(ssts)
+    }
+
+    /// Scans all keys of `cf` in `[start, end)` of the kv engine and returns
+    /// the number of keys scanned. RocksDB verifies block checksums on every
+    /// read, so a successful scan also proves the covered SST blocks are not
+    /// corrupted. An empty `end` means the end of the keyspace.
+    pub fn verify_range_checksum(&self, cf: &str, start: &[u8], end: &[u8]) -> Result<u64> {
+        validate_db_and_cf(DBType::KV, cf)?;
+        let end = if end.is_empty() {
+            keys::data_end_key(b"")
+        } else {
+            end.to_vec()
+        };
+        let mut scanned = 0;
+        box_try!(self.engines.kv.scan_cf(cf, start, &end, false, |_, _| {
+            scanned += 1;
+            Ok(true)
+        }));
+        Ok(scanned)
+    }
 }
 
 fn recover_mvcc_for_range(
@@ -123,4 +123,10 @@ lazy_static! {
         &["cf", "name"]
     )
     .unwrap();
+    pub static ref DATA_PATH_SIZE_GAUGE_VEC: IntGaugeVec = register_int_gauge_vec!(
+        "tikv_data_path_size_bytes",
+        "Capacity and available size of each data path",
+        &["path", "type"]
+    )
+    .unwrap();
 }
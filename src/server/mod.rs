@@ -4,6 +4,7 @@ mod metrics;
 mod raft_client;
 mod service;
 
+pub mod block_cache_warmup;
 pub mod config;
 pub mod debug;
 pub mod errors;
@@ -13,12 +14,14 @@ pub mod readpool;
 pub mod resolve;
 pub mod server;
 pub mod snap;
+pub mod stats_monitor;
 pub mod status_server;
 pub mod transport;
 
 pub use self::config::{Config, DEFAULT_CLUSTER_ID, DEFAULT_LISTENING_ADDR};
 pub use self::errors::{Error, Result};
 pub use self::metrics::CONFIG_ROCKSDB_GAUGE;
+pub use self::metrics::DATA_PATH_SIZE_GAUGE_VEC;
 pub use self::node::{create_raft_storage, Node};
 pub use self::raft_client::RaftClient;
 pub use self::resolve::{PdStoreAddrResolver, StoreAddrResolver};
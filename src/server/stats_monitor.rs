@@ -0,0 +1,123 @@
+// Copyright 2019 TiKV Project Authors. Licensed under Apache-2.0.
+
+//! A monitor that periodically collects extended store stats — CPU usage of
+//! each thread pool and engine disk latencies — and forwards them to the PD
+//! worker, which exports them along with the store heartbeat stats.
+
+use std::io;
+use std::sync::mpsc::{self, Sender};
+use std::sync::Arc;
+use std::thread::{Builder, JoinHandle};
+use std::time::{Duration, Instant};
+
+use engine::rocks::{DBStatisticsHistogramType as HistType, DB};
+
+use super::load_statistics::{ThreadLoad, ThreadLoadStatistics};
+use super::server::GRPC_THREAD_PREFIX;
+use crate::pd::PdTask;
+use tikv_util::collections::HashMap;
+use tikv_util::worker::FutureScheduler;
+
+pub const DEFAULT_STATS_MONITOR_INTERVAL: u64 = 10_000; // 10s
+
+const THREAD_LOAD_SLOTS: usize = 2;
+
+/// The thread pools whose CPU usage is collected, by thread name prefix.
+const MONITORED_POOLS: &[&str] = &[
+    GRPC_THREAD_PREFIX,
+    "raftstore",
+    "apply",
+    "store-read",
+    "cop",
+    "sched-worker",
+];
+
+/// The engine latency histograms that are collected, with the name they are
+/// reported under.
+const MONITORED_LATENCIES: &[(&str, HistType)] = &[
+    ("get", HistType::DbGet),
+    ("write", HistType::DbWrite),
+    ("wal_sync", HistType::WalFileSyncMicros),
+];
+
+pub struct StatsMonitor {
+    kv_engine: Arc<DB>,
+    pd_sender: FutureScheduler<PdTask>,
+    interval: Duration,
+    handle: Option<JoinHandle<()>>,
+    sender: Option<Sender<bool>>,
+}
+
+impl StatsMonitor {
+    pub fn new(
+        kv_engine: Arc<DB>,
+        pd_sender: FutureScheduler<PdTask>,
+        interval: Duration,
+    ) -> StatsMonitor {
+        StatsMonitor {
+            kv_engine,
+            pd_sender,
+            interval,
+            handle: None,
+            sender: None,
+        }
+    }
+
+    /// Starts collecting in a background thread. Call this after the monitored
+    /// thread pools are initialized, otherwise their threads can't be caught.
+    pub fn start(&mut self) -> Result<(), io::Error> {
+        let kv_engine = Arc::clone(&self.kv_engine);
+        let pd_sender = self.pd_sender.clone();
+        let interval = self.interval;
+        let (tx, rx) = mpsc::channel();
+        self.sender = Some(tx);
+        let h = Builder::new()
+            .name(thd_name!("stats-monitor"))
+            .spawn(move || {
+                let mut pool_loads: Vec<_> = MONITORED_POOLS
+                    .iter()
+                    .map(|pool| {
+                        let load = Arc::new(ThreadLoad::with_threshold(0));
+                        let stats =
+                            ThreadLoadStatistics::new(THREAD_LOAD_SLOTS, pool, Arc::clone(&load));
+                        (*pool, load, stats)
+                    })
+                    .collect();
+                while let Err(mpsc::RecvTimeoutError::Timeout) = rx.recv_timeout(interval) {
+                    let now = Instant::now();
+                    let mut cpu_usages = HashMap::default();
+                    for (pool, load, stats) in &mut pool_loads {
+                        stats.record(now);
+                        cpu_usages.insert(pool.to_string(), load.load() as u64);
+                    }
+                    let disk_latencies = MONITORED_LATENCIES
+                        .iter()
+                        .filter_map(|(name, t)| {
+                            kv_engine
+                                .get_statistics_histogram(*t)
+                                .map(|data| (name.to_string(), data.average as u64))
+                        })
+                        .collect();
+                    if let Err(e) = pd_sender.schedule(PdTask::UpdateExtendedStats {
+                        cpu_usages,
+                        disk_latencies,
+                    }) {
+                        error!("failed to send extended store stats"; "err" => ?e);
+                    }
+                }
+            })?;
+        self.handle = Some(h);
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        let h = self.handle.take();
+        if h.is_none() {
+            return;
+        }
+        drop(self.sender.take().unwrap());
+        if let Err(e) = h.unwrap().join() {
+            error!("join stats monitor failed"; "err" => ?e);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright 2018 TiKV Project Authors. Licensed under Apache-2.0.
 
-use std::cmp::Ordering;
+use std::cmp::{self, Ordering};
+use std::collections::HashMap;
 use std::fmt::{self, Display, Formatter};
 use std::mem;
 use std::sync::mpsc;
@@ -67,6 +68,58 @@ impl<T: PdClient + 'static> GCSafePointProvider for Arc<T> {
     }
 }
 
+/// A GC safe point held by a long-running service such as backup.
+struct ServiceSafePoint {
+    safe_point: u64,
+    expire_at: Instant,
+}
+
+/// Holds the GC safe points registered by long-running services, so data they
+/// still read won't be garbage collected. Auto GC won't pass the minimum of
+/// all unexpired service safe points.
+///
+/// PD of this version doesn't manage service safe points, so a hold only
+/// takes effect on the local store.
+#[derive(Clone, Default)]
+pub struct ServiceSafePointRegistry {
+    services: Arc<Mutex<HashMap<String, ServiceSafePoint>>>,
+}
+
+impl ServiceSafePointRegistry {
+    /// Registers or refreshes the GC safe point held by the given service.
+    /// The hold is dropped automatically when `ttl` elapses without a refresh.
+    pub fn update(&self, service: &str, safe_point: u64, ttl: Duration) {
+        GC_SERVICE_SAFE_POINT_GAUGE_VEC
+            .with_label_values(&[service])
+            .set(safe_point as i64);
+        self.services.lock().unwrap().insert(
+            service.to_owned(),
+            ServiceSafePoint {
+                safe_point,
+                expire_at: Instant::now() + ttl,
+            },
+        );
+    }
+
+    /// Returns the minimum safe point held by unexpired services, dropping the
+    /// expired ones on the way. `None` means no service holds the safe point
+    /// back.
+    fn limit(&self) -> Option<u64> {
+        let mut services = self.services.lock().unwrap();
+        let now = Instant::now();
+        services.retain(|service, s| {
+            if s.expire_at <= now {
+                info!("gc_worker: service safe point expired"; "service" => %service);
+                let _ = GC_SERVICE_SAFE_POINT_GAUGE_VEC.remove_label_values(&[service]);
+                false
+            } else {
+                true
+            }
+        });
+        services.values().map(|s| s.safe_point).min()
+    }
+}
+
 enum GCTask {
     GC {
         ctx: Context,
@@ -680,6 +733,9 @@ struct GCManager<S: GCSafePointProvider, R: RegionInfoProvider> {
 
     safe_point_last_check_time: Instant,
 
+    /// Safe points held by services, which hold back the safe point from PD.
+    service_safe_points: ServiceSafePointRegistry,
+
     /// Used to schedule `GCTask`s.
     worker_scheduler: worker::Scheduler<GCTask>,
 
@@ -690,12 +746,14 @@ struct GCManager<S: GCSafePointProvider, R: RegionInfoProvider> {
 impl<S: GCSafePointProvider, R: RegionInfoProvider> GCManager<S, R> {
     pub fn new(
         cfg: AutoGCConfig<S, R>,
+        service_safe_points: ServiceSafePointRegistry,
         worker_scheduler: worker::Scheduler<GCTask>,
     ) -> GCManager<S, R> {
         GCManager {
             cfg,
             safe_point: 0,
             safe_point_last_check_time: Instant::now(),
+            service_safe_points,
             worker_scheduler,
             gc_manager_ctx: GCManagerContext::new(),
         }
@@ -789,15 +847,31 @@ impl<S: GCSafePointProvider, R: RegionInfoProvider> GCManager<S, R> {
             }
         };
 
-        match safe_point.cmp(&self.safe_point) {
-            Ordering::Less => {
-                panic!(
-                    "got new safe point {} which is less than current safe point {}. \
-                     there must be something wrong.",
-                    safe_point, self.safe_point
+        if safe_point < self.safe_point {
+            panic!(
+                "got new safe point {} which is less than current safe point {}. \
+                 there must be something wrong.",
+                safe_point, self.safe_point
+            );
+        }
+
+        // Services may hold the safe point back, so the snapshots they still
+        // read are not garbage collected. The safe point we have worked with
+        // never regresses, even if a service holds one below it.
+        let safe_point = match self.service_safe_points.limit() {
+            Some(limit) if limit < safe_point => {
+                info!(
+                    "gc_worker: safe point is held back by services";
+                    "safe_point" => safe_point,
+                    "limit" => limit
                 );
+                cmp::max(limit, self.safe_point)
             }
-            Ordering::Equal => false,
+            _ => safe_point,
+        };
+
+        match safe_point.cmp(&self.safe_point) {
+            Ordering::Less | Ordering::Equal => false,
             Ordering::Greater => {
                 debug!("gc_worker: update safe point"; "safe_point" => safe_point);
                 self.safe_point = safe_point;
@@ -1063,6 +1137,8 @@ pub struct GCWorker<E: Engine> {
     worker: Arc<Mutex<Worker<GCTask>>>,
     worker_scheduler: worker::Scheduler<GCTask>,
 
+    service_safe_points: ServiceSafePointRegistry,
+
     gc_manager_handle: Arc<Mutex<Option<GCManagerHandle>>>,
 }
 
@@ -1086,6 +1162,7 @@ impl<E: Engine> GCWorker<E> {
             ratio_threshold,
             worker,
             worker_scheduler,
+            service_safe_points: ServiceSafePointRegistry::default(),
             gc_manager_handle: Arc::new(Mutex::new(None)),
         }
     }
@@ -1096,11 +1173,23 @@ impl<E: Engine> GCWorker<E> {
     ) -> Result<()> {
         let mut handle = self.gc_manager_handle.lock().unwrap();
         assert!(handle.is_none());
-        let new_handle = GCManager::new(cfg, self.worker_scheduler.clone()).start()?;
+        let new_handle = GCManager::new(
+            cfg,
+            self.service_safe_points.clone(),
+            self.worker_scheduler.clone(),
+        )
+        .start()?;
         *handle = Some(new_handle);
         Ok(())
     }
 
+    /// Registers or refreshes the GC safe point held by the given service,
+    /// so auto GC won't collect the data the service still reads. See
+    /// `ServiceSafePointRegistry` for details.
+    pub fn update_service_gc_safe_point(&self, service: &str, safe_point: u64, ttl: Duration) {
+        self.service_safe_points.update(service, safe_point, ttl);
+    }
+
     pub fn start(&mut self) -> Result<()> {
         let runner = GCRunner::new(
             self.engine.clone(),
@@ -1219,6 +1308,7 @@ mod tests {
         worker: Worker<GCTask>,
         safe_point_sender: Sender<u64>,
         gc_task_receiver: Receiver<GCTask>,
+        service_safe_points: ServiceSafePointRegistry,
     }
 
     impl GCManagerTestUtil {
@@ -1239,12 +1329,14 @@ mod tests {
             cfg.poll_safe_point_interval = Duration::from_millis(100);
             cfg.always_check_safe_point = true;
 
-            let gc_manager = GCManager::new(cfg, worker.scheduler());
+            let service_safe_points = ServiceSafePointRegistry::default();
+            let gc_manager = GCManager::new(cfg, service_safe_points.clone(), worker.scheduler());
             Self {
                 gc_manager: Some(gc_manager),
                 worker,
                 safe_point_sender,
                 gc_task_receiver,
+                service_safe_points,
             }
         }
 
@@ -1363,6 +1455,38 @@ mod tests {
         test_util.stop();
     }
 
+    #[test]
+    fn test_service_gc_safe_point() {
+        let mut test_util = GCManagerTestUtil::new(BTreeMap::new());
+        let mut gc_manager = test_util.gc_manager.take().unwrap();
+
+        // A service safe point holds back the safe point from PD.
+        test_util
+            .service_safe_points
+            .update("backup", 200, Duration::from_secs(1000));
+        test_util.add_next_safe_point(233);
+        assert!(gc_manager.try_update_safe_point());
+        assert_eq!(gc_manager.safe_point, 200);
+
+        // Refreshing the hold with a newer safe point releases it accordingly.
+        test_util
+            .service_safe_points
+            .update("backup", 300, Duration::from_secs(1000));
+        test_util.add_next_safe_point(233);
+        assert!(gc_manager.try_update_safe_point());
+        assert_eq!(gc_manager.safe_point, 233);
+
+        // An expired hold is dropped and doesn't block GC any more.
+        test_util
+            .service_safe_points
+            .update("backup", 200, Duration::from_secs(0));
+        test_util.add_next_safe_point(250);
+        assert!(gc_manager.try_update_safe_point());
+        assert_eq!(gc_manager.safe_point, 250);
+
+        test_util.stop();
+    }
+
     #[test]
     fn test_gc_manager_initialize() {
         let mut test_util = GCManagerTestUtil::new(BTreeMap::new());
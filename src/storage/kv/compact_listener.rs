@@ -5,6 +5,7 @@ use std::cmp;
 use crate::raftstore::coprocessor::properties::RangeProperties;
 use engine::rocks::{CompactionJobInfo, EventListener};
 use tikv_util::collections::hash_set_with_capacity;
+use tikv_util::io_tags::{add_io_bytes, IOOp, IOType};
 
 pub struct CompactedEvent {
     pub cf: String,
@@ -60,6 +61,9 @@ impl EventListener for CompactionListener {
             return;
         }
 
+        add_io_bytes(IOType::Compaction, IOOp::Read, info.total_input_bytes());
+        add_io_bytes(IOType::Compaction, IOOp::Write, info.total_output_bytes());
+
         if let Some(ref f) = self.filter {
             if !f(info) {
                 return;
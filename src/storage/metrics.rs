@@ -238,6 +238,12 @@ lazy_static! {
         &["type"]
     )
     .unwrap();
+    pub static ref GC_SERVICE_SAFE_POINT_GAUGE_VEC: IntGaugeVec = register_int_gauge_vec!(
+        "tikv_gcworker_service_safe_point",
+        "Safe point held back by each service",
+        &["service"]
+    )
+    .unwrap();
     pub static ref REQUEST_EXCEED_BOUND: IntCounter = register_int_counter!(
         "tikv_request_exceed_bound",
         "Counter of request exceed bound"
@@ -13,6 +13,7 @@ pub mod types;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::io::Error as IoError;
 use std::sync::{atomic, Arc, Mutex};
+use std::time::Duration;
 use std::{cmp, error, u64};
 
 use engine::rocks::DB;
@@ -30,7 +31,7 @@ use self::metrics::*;
 use self::mvcc::Lock;
 
 pub use self::config::{BlockCacheConfig, Config, DEFAULT_DATA_DIR, DEFAULT_ROCKSDB_SUB_DIR};
-pub use self::gc_worker::{AutoGCConfig, GCSafePointProvider};
+pub use self::gc_worker::{AutoGCConfig, GCSafePointProvider, ServiceSafePointRegistry};
 pub use self::kv::raftkv::RaftKv;
 pub use self::kv::{
     destroy_tls_engine, set_tls_engine, with_tls_engine, CFStatistics, Cursor, CursorBuilder,
@@ -696,6 +697,14 @@ impl<E: Engine> Storage<E> {
         self.gc_worker.start_auto_gc(cfg)
     }
 
+    /// Registers or refreshes the GC safe point held by a long-running service
+    /// (e.g. backup), so auto GC won't collect the data it still reads. The
+    /// hold expires when `ttl` elapses without a refresh.
+    pub fn update_service_gc_safe_point(&self, service: &str, safe_point: u64, ttl: Duration) {
+        self.gc_worker
+            .update_service_gc_safe_point(service, safe_point, ttl);
+    }
+
     /// Get the underlying `Engine` of the `Storage`.
     pub fn get_engine(&self) -> E {
         self.engine.clone()
@@ -10,6 +10,7 @@ use crate::pd::PdTask;
 use crate::server::readpool::{self, Builder, Config, ReadPool};
 use crate::storage::kv::{destroy_tls_engine, set_tls_engine};
 use tikv_util::collections::HashMap;
+use tikv_util::io_tags::{add_io_bytes, IOOp, IOType};
 use tikv_util::worker::FutureScheduler;
 
 use super::metrics::*;
@@ -92,6 +93,9 @@ fn tls_flush(pd_sender: &FutureScheduler<PdTask>) {
         let read_stats = storage_metrics.local_read_flow_stats.clone();
         storage_metrics.local_read_flow_stats = HashMap::default();
 
+        let read_bytes = read_stats.values().map(|s| s.read_bytes as u64).sum();
+        add_io_bytes(IOType::ForegroundRead, IOOp::Read, read_bytes);
+
         let result = pd_sender.schedule(PdTask::ReadStats { read_stats });
         if let Err(e) = result {
             error!("Failed to send read pool read flow statistics"; "err" => ?e);
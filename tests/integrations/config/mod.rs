@@ -128,6 +128,8 @@ fn test_serde_custom_tikv_config() {
         region_compact_check_step: 1_234,
         region_compact_min_tombstones: 999,
         region_compact_tombstones_percent: 33,
+        region_compact_schedule_window: "01:00-05:30".to_owned(),
+        region_compact_schedule_bottommost: true,
         pd_heartbeat_tick_interval: ReadableDuration::minutes(12),
         pd_store_heartbeat_tick_interval: ReadableDuration::secs(12),
         notify_capacity: 12_345,
@@ -167,6 +169,7 @@ fn test_serde_custom_tikv_config() {
         wal_dir: "/var".to_owned(),
         wal_ttl_seconds: 1,
         wal_size_limit: ReadableSize::kb(1),
+        recycle_log_file_num: 12,
         max_total_wal_size: ReadableSize::gb(1),
         max_background_jobs: 12,
         max_manifest_file_size: ReadableSize::mb(12),
@@ -188,6 +191,8 @@ fn test_serde_custom_tikv_config() {
         writable_file_max_buffer_size: ReadableSize::mb(12),
         use_direct_io_for_flush_and_compaction: true,
         enable_pipelined_write: false,
+        secondary_data_dir: "/cold".to_owned(),
+        primary_data_target_size: ReadableSize::gb(12),
         defaultcf: DefaultCfConfig {
             block_size: ReadableSize::kb(12),
             block_cache_size: ReadableSize::gb(12),
@@ -409,6 +414,7 @@ fn test_serde_custom_tikv_config() {
         wal_dir: "/var".to_owned(),
         wal_ttl_seconds: 1,
         wal_size_limit: ReadableSize::kb(12),
+        recycle_log_file_num: 4,
         max_total_wal_size: ReadableSize::gb(1),
         max_background_jobs: 12,
         max_manifest_file_size: ReadableSize::mb(12),
@@ -505,6 +511,7 @@ fn test_serde_custom_tikv_config() {
     value.import = ImportConfig {
         num_threads: 123,
         stream_channel_window: 123,
+        bandwidth_limit: ReadableSize::mb(123),
     };
     value.panic_when_unexpected_key_or_data = true;
 